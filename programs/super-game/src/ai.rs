@@ -1,4 +1,5 @@
 use crate::errors::GameError;
+use crate::hex;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
@@ -7,6 +8,7 @@ struct MoveAction {
     from_col: usize,
     to_row: usize,
     to_col: usize,
+    move_cost: u8,
 }
 
 const DESIRED_UNIT_QUANTITY: u16 = 30;
@@ -65,15 +67,20 @@ fn attack_adjacent_tiles(
         if let Some(tile) = tile {
             if let Some(units) = &tile.units {
                 if units.stamina > 0 {
-                    let adjacent_positions = get_adjacent_tiles(row_index, col_index, game);
+                    let adjacent_positions = hex::neighbours(&game.tiles, (row_index, col_index));
 
                     for (adj_row, adj_col) in adjacent_positions {
-                        if adj_row >= game.tiles.len() || adj_col >= game.tiles[adj_row].len() {
-                            continue;
-                        }
-
                         let adj_tile_option = &game.tiles[adj_row][adj_col];
                         if let Some(adj_tile) = adj_tile_option {
+                            let move_cost = adj_tile
+                                .terrain
+                                .stamina_cost(units.unit_type, units.stamina);
+                            if !adj_tile.terrain.is_passable(units.unit_type)
+                                || units.stamina < move_cost
+                            {
+                                continue;
+                            }
+
                             if adj_tile.owner != bot_pubkey {
                                 // Check if it makes sense to attack
                                 let bot_strength =
@@ -112,6 +119,7 @@ fn attack_adjacent_tiles(
                                         from_col: col_index,
                                         to_row: adj_row,
                                         to_col: adj_col,
+                                        move_cost,
                                     });
                                     break;
                                 }
@@ -130,18 +138,21 @@ fn attack_adjacent_tiles(
             continue;
         }
 
+        let move_cost = action.move_cost;
         let (from_tile_option, to_tile_option) = get_tile_options(game, action)?;
 
         let from_tile = from_tile_option.as_mut().ok_or(GameError::InvalidTile)?;
         let to_tile = to_tile_option.as_mut().ok_or(GameError::InvalidTile)?;
 
-        if let Some(destroyed_player_pubkey) = handle_attack(from_tile, to_tile, bot_pubkey)? {
+        if let Some(destroyed_player_pubkey) =
+            handle_attack(from_tile, to_tile, bot_pubkey, move_cost)?
+        {
             players_to_update.push(destroyed_player_pubkey);
         }
     }
 
     for player_pubkey in players_to_update {
-        update_player_status(game, player_pubkey, false);
+        game.eliminate_player(player_pubkey, Some(bot_pubkey));
     }
 
     Ok(())
@@ -151,6 +162,7 @@ fn handle_attack(
     from_tile: &mut Tile,
     to_tile: &mut Tile,
     bot_pubkey: Pubkey,
+    move_cost: u8,
 ) -> Result<Option<Pubkey>> {
     let from_units = from_tile.units.as_ref().ok_or(GameError::InvalidTile)?;
 
@@ -159,8 +171,6 @@ fn handle_attack(
     let from_unit_strength = from_units.unit_type.strength() as u32;
     let from_unit_stamina = from_units.stamina;
 
-    let move_cost = 1;
-
     from_tile.units = None;
 
     let attacker_strength = from_unit_quantity as u32 * from_unit_strength;
@@ -193,12 +203,7 @@ fn handle_attack(
             // Both units die
             to_tile.units = None;
 
-            if let Some(building) = &to_tile.building {
-                if let BuildingType::Base = building.building_type {
-                    base_destroyed_player = Some(to_tile.owner);
-                    to_tile.building = None;
-                }
-            }
+            base_destroyed_player = to_tile.capture_capital(bot_pubkey);
         }
         std::cmp::Ordering::Less => {
             // Attacker loses
@@ -207,8 +212,7 @@ fn handle_attack(
                 defender_unit_strength.saturating_sub(adjusted_attacker_strength);
             if defender_unit_strength > 0 {
                 let unit_strength = to_tile.units.as_ref().unwrap().unit_type.strength() as u32;
-                let remaining_defender_units =
-                    (remaining_defender_strength + unit_strength - 1) / unit_strength;
+                let remaining_defender_units = remaining_defender_strength.div_ceil(unit_strength);
                 let original_quantity = to_tile.units.as_ref().unwrap().quantity;
                 to_tile.units.as_mut().unwrap().quantity =
                     remaining_defender_units.min(original_quantity as u32) as u16;
@@ -220,8 +224,7 @@ fn handle_attack(
             // Attacker wins
 
             let remaining_attacker_strength = adjusted_attacker_strength - defender_strength;
-            let remaining_attacker_units =
-                (remaining_attacker_strength + from_unit_strength - 1) / from_unit_strength;
+            let remaining_attacker_units = remaining_attacker_strength.div_ceil(from_unit_strength);
             let remaining_stamina = from_unit_stamina.saturating_sub(move_cost);
 
             to_tile.units = Some(Units {
@@ -230,12 +233,7 @@ fn handle_attack(
                 stamina: remaining_stamina,
             });
 
-            if let Some(building) = &to_tile.building {
-                if let BuildingType::Base = building.building_type {
-                    base_destroyed_player = Some(to_tile.owner);
-                    to_tile.building = None;
-                }
-            }
+            base_destroyed_player = to_tile.capture_capital(bot_pubkey);
             to_tile.owner = bot_pubkey;
         }
     }
@@ -255,6 +253,11 @@ fn recruit_units(game: &mut Game, bot_index: usize, bot_tiles: &[(usize, usize)]
             .as_mut()
             .ok_or(GameError::InvalidTile)?;
 
+        // tiles captured by planes can't hold infantry
+        if !tile.terrain.is_passable(UnitType::Infantry) {
+            continue;
+        }
+
         let current_quantity = if let Some(units) = &tile.units {
             units.quantity
         } else {
@@ -350,7 +353,7 @@ fn build_constructions(
                 let tile = game.tiles[row_index][col_index]
                     .as_mut()
                     .ok_or(GameError::InvalidTile)?;
-                if tile.building.is_none() {
+                if tile.building.is_none() && tile.terrain.is_passable(UnitType::Infantry) {
                     tile.building = Some(Building {
                         building_type: BuildingType::GasPlant,
                         level: 1,
@@ -402,42 +405,6 @@ fn has_building(
     false
 }
 
-fn get_adjacent_tiles(row: usize, col: usize, game: &Game) -> Vec<(usize, usize)> {
-    let mut positions = Vec::new();
-    let max_row = game.tiles.len() as isize;
-    let max_col = if max_row > 0 {
-        game.tiles[0].len() as isize
-    } else {
-        0
-    };
-
-    let directions = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
-
-    for (dx, dy) in directions {
-        let new_row = row as isize + dx;
-        let new_col = col as isize + dy;
-        if new_row >= 0 && new_row < max_row && new_col >= 0 && new_col < max_col {
-            positions.push((new_row as usize, new_col as usize));
-        }
-    }
-
-    positions
-}
-
-fn update_player_status(game: &mut Game, player_pubkey: Pubkey, is_alive: bool) {
-    if let Some(player_index) = game.players.iter().position(|player_option| {
-        if let Some(player_info) = player_option {
-            player_info.pubkey == player_pubkey
-        } else {
-            false
-        }
-    }) {
-        if let Some(player_info) = &mut game.players[player_index] {
-            player_info.is_alive = is_alive;
-        }
-    }
-}
-
 fn get_tile_options(
     game: &mut Game,
     action: MoveAction,
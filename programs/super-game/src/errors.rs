@@ -42,6 +42,76 @@ pub enum GameError {
     InsufficientFunds,
     #[msg("Too many units")]
     TooManyUnits,
+    #[msg("The game is not live")]
+    GameNotLive,
+    #[msg("Player has already been eliminated")]
+    PlayerEliminated,
+    #[msg("The creator must cancel the game instead of leaving")]
+    CreatorCannotLeave,
+    #[msg("Player profile account is missing")]
+    MissingPlayerProfile,
+    #[msg("The game is not completed yet")]
+    GameNotCompleted,
+    #[msg("The game has already been settled")]
+    GameAlreadySettled,
+    #[msg("The game must be settled first")]
+    GameNotSettled,
+    #[msg("Game index entry account is missing")]
+    MissingGameIndexEntry,
+    #[msg("Turn time limit is out of bounds")]
+    InvalidTurnTimeLimit,
+    #[msg("Turn time limit has expired")]
+    TurnTimeExpired,
+    #[msg("Total number of players must be between 2 and 4")]
+    InvalidPlayerCount,
+    #[msg("At least one human seat is required")]
+    InvalidHumanSeats,
+    #[msg("Not enough bots available")]
+    TooManyBots,
+    #[msg("Starting balance is too high")]
+    InvalidStartingBalance,
+    #[msg("Invalid number of starting units")]
+    InvalidStartingUnits,
+    #[msg("Starting attack points exceed the maximum")]
+    InvalidStartingAttackPoints,
+    #[msg("Too many invited players")]
+    TooManyInvites,
+    #[msg("You are not invited to this game")]
+    NotInvited,
+    #[msg("Invalid join code")]
+    InvalidJoinCode,
+    #[msg("Not every player in the lobby is ready")]
+    PlayersNotReady,
+    #[msg("Player is already in the matchmaking queue")]
+    AlreadyInQueue,
+    #[msg("Player is not in the matchmaking queue")]
+    NotInQueue,
+    #[msg("Matchmaking queue is full")]
+    MatchmakingQueueFull,
+    #[msg("No compatible players in the matchmaking queue")]
+    NoMatchFound,
+    #[msg("A map seed commitment is required")]
+    MissingCommitment,
+    #[msg("Game is not waiting for map seed reveals")]
+    GameNotRevealing,
+    #[msg("Map seed secret already revealed")]
+    AlreadyRevealed,
+    #[msg("Revealed secret does not match the commitment")]
+    InvalidReveal,
+    #[msg("Reveal deadline has not passed yet")]
+    RevealNotExpired,
+    #[msg("Invalid map template")]
+    InvalidMapTemplate,
+    #[msg("Units can't enter this terrain")]
+    ImpassableTerrain,
+    #[msg("Queue entry has not expired yet")]
+    QueueEntryNotExpired,
+    #[msg("Player was kicked from this game")]
+    PlayerKicked,
+    #[msg("Too many players kicked from this game")]
+    KickListFull,
+    #[msg("Multiplayer games can't use an explicit seed")]
+    ExplicitSeedNotAllowed,
 }
 
 #[error_code]
@@ -76,4 +146,6 @@ pub enum ConstructionError {
     MaxLevelReached,
     #[msg("Cannot build a Base")]
     CannotBuildBase,
+    #[msg("Cannot build on this terrain")]
+    InvalidTerrain,
 }
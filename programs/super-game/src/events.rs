@@ -0,0 +1,22 @@
+use crate::states::AfkAction;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PlayerAfk {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub missed_turns: u8,
+    pub action: AfkAction,
+}
+
+// The lobby registry was full of recent lobbies, the game can only be joined by its address
+#[event]
+pub struct LobbyNotListed {
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct PlayersMatched {
+    pub game: Pubkey,
+    pub players: Vec<Pubkey>,
+}
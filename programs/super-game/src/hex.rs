@@ -0,0 +1,330 @@
+use crate::states::{Terrain, Tile};
+
+// Hex coordinates of the map. Tiles are stored as (row, col) in the square grid with
+// each row centred, and even rows are drawn half a tile to the right ("even-r" offset
+// layout), so every tile has up to six neighbours. Player moves, bot moves and
+// clients all go through these functions.
+
+pub type Position = (usize, usize);
+
+// Axial (q, r) offsets of the six neighbours, clockwise from the east
+const DIRECTIONS: [(isize, isize); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+fn to_axial((row, col): Position) -> (isize, isize) {
+    let row = row as isize;
+    let col = col as isize;
+    (col - (row + (row & 1)) / 2, row)
+}
+
+fn from_axial(q: isize, r: isize) -> Option<Position> {
+    let col = q + (r + (r & 1)) / 2;
+    (r >= 0 && col >= 0).then_some((r as usize, col as usize))
+}
+
+// A position is inside the layout when the grid holds a tile there, the corners of
+// the bounding square are empty
+pub fn in_layout(tiles: &[Vec<Option<Tile>>], (row, col): Position) -> bool {
+    tiles
+        .get(row)
+        .and_then(|tiles_row| tiles_row.get(col))
+        .is_some_and(|tile| tile.is_some())
+}
+
+pub fn neighbours(tiles: &[Vec<Option<Tile>>], position: Position) -> Vec<Position> {
+    let (q, r) = to_axial(position);
+    DIRECTIONS
+        .iter()
+        .filter_map(|&(dq, dr)| from_axial(q + dq, r + dr))
+        .filter(|&neighbour| in_layout(tiles, neighbour))
+        .collect()
+}
+
+pub fn distance(from: Position, to: Position) -> usize {
+    let (from_q, from_r) = to_axial(from);
+    let (to_q, to_r) = to_axial(to);
+    let dq = from_q - to_q;
+    let dr = from_r - to_r;
+    (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs()) / 2
+}
+
+pub fn are_neighbours(from: Position, to: Position) -> bool {
+    distance(from, to) == 1
+}
+
+// Moves a position by the offset from `from` to `to`, shapes keep their form
+pub fn translate(position: Position, from: Position, to: Position) -> Option<Position> {
+    let (q, r) = to_axial(position);
+    let (from_q, from_r) = to_axial(from);
+    let (to_q, to_r) = to_axial(to);
+    from_axial(q + to_q - from_q, r + to_r - from_r)
+}
+
+// The hex symmetry of the layouts, whose row widths read the same from both ends.
+// With an odd number of rows every row keeps its offset, so the map mirrors top to
+// bottom. With an even number the offsets of mirrored rows differ, only a half turn
+// lines them up.
+pub fn mirror((row, col): Position, grid_size: usize) -> Position {
+    if grid_size % 2 == 1 {
+        (grid_size - 1 - row, col)
+    } else {
+        (grid_size - 1 - row, grid_size - 1 - col)
+    }
+}
+
+// Tiles crossed by a straight line between the centres of two tiles, both ends included.
+// Not called by any instruction yet, clients use it and has_line_of_sight to draw ranged
+// attacks. Integer only so it gives the same tiles on every machine.
+pub fn line(from: Position, to: Position) -> Vec<Position> {
+    let steps = distance(from, to) as isize;
+    if steps == 0 {
+        return vec![from];
+    }
+
+    let (from_q, from_r) = to_axial(from);
+    let (to_q, to_r) = to_axial(to);
+    let (from_s, to_s) = (-from_q - from_r, -to_q - to_r);
+    let denominator = steps * LINE_SCALE;
+
+    (0..=steps)
+        .filter_map(|step| {
+            let lerp = |from: isize, to: isize, nudge: isize| {
+                (from * (steps - step) + to * step) * LINE_SCALE + nudge
+            };
+            let (q, r) = round_cube(
+                lerp(from_q, to_q, LINE_NUDGE[0]),
+                lerp(from_r, to_r, LINE_NUDGE[1]),
+                lerp(from_s, to_s, LINE_NUDGE[2]),
+                denominator,
+            );
+            from_axial(q, r)
+        })
+        .collect()
+}
+
+// Points are nudged off tile edges so ties always round the same way. The nudge sums to
+// zero to stay on the q + r + s = 0 plane and is below half a step for any scale.
+const LINE_SCALE: isize = 8;
+const LINE_NUDGE: [isize; 3] = [1, 2, -3];
+
+// Rounds cube coordinates given as fractions of `denominator` to the nearest tile
+fn round_cube(q: isize, r: isize, s: isize, denominator: isize) -> (isize, isize) {
+    let round = |value: isize| (2 * value + denominator).div_euclid(2 * denominator);
+    let mut rounded_q = round(q);
+    let mut rounded_r = round(r);
+    let rounded_s = round(s);
+
+    let q_diff = (rounded_q * denominator - q).abs();
+    let r_diff = (rounded_r * denominator - r).abs();
+    let s_diff = (rounded_s * denominator - s).abs();
+
+    if q_diff > r_diff && q_diff > s_diff {
+        rounded_q = -rounded_r - rounded_s;
+    } else if r_diff > s_diff {
+        rounded_r = -rounded_q - rounded_s;
+    }
+
+    (rounded_q, rounded_r)
+}
+
+// Both ends and every tile in between must be inside the layout,
+// mountains in between block the view
+pub fn has_line_of_sight(tiles: &[Vec<Option<Tile>>], from: Position, to: Position) -> bool {
+    if !in_layout(tiles, from) || !in_layout(tiles, to) {
+        return false;
+    }
+
+    let path = line(from, to);
+    path.iter()
+        .skip(1)
+        .take(path.len().saturating_sub(2))
+        .all(|&(row, col)| {
+            in_layout(tiles, (row, col))
+                && tiles[row][col].is_some_and(|tile| tile.terrain != Terrain::Mountains)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::initialize_tiles;
+    use crate::states::{GameSettings, MapGeneration, MapSize, PlayerInfo};
+    use anchor_lang::prelude::Pubkey;
+
+    const MAP_SIZES: [MapSize; 4] = [
+        MapSize::Small,
+        MapSize::Large,
+        MapSize::Medium,
+        MapSize::ExtraLarge,
+    ];
+
+    fn generate(map_size: MapSize, map_generation: MapGeneration) -> Vec<Vec<Option<Tile>>> {
+        let mut settings = GameSettings::matchmaking(map_size, 4);
+        settings.map_generation = map_generation;
+        let player_infos: Vec<PlayerInfo> = (0..4)
+            .map(|_| PlayerInfo::new(Pubkey::new_unique(), false, &settings))
+            .collect();
+        initialize_tiles(&[7; 32], &player_infos, &settings)
+    }
+
+    #[test]
+    fn distance_counts_hex_steps() {
+        assert_eq!(distance((3, 3), (3, 3)), 0);
+        assert_eq!(distance((3, 3), (3, 5)), 2);
+        // odd rows reach down-left and down-right without a sideways step
+        assert_eq!(distance((1, 1), (2, 0)), 1);
+        assert_eq!(distance((1, 1), (2, 1)), 1);
+        assert_eq!(distance((1, 1), (2, 2)), 2);
+        assert_eq!(distance((0, 0), (4, 2)), 4);
+        assert_eq!(distance((4, 2), (0, 0)), 4);
+    }
+
+    #[test]
+    fn neighbours_are_one_step_away() {
+        let tiles = generate(MapSize::Small, MapGeneration::Random);
+        let mut found = neighbours(&tiles, (3, 3));
+        found.sort();
+        assert_eq!(found, vec![(2, 2), (2, 3), (3, 2), (3, 4), (4, 2), (4, 3)]);
+        assert!(found
+            .iter()
+            .all(|&neighbour| are_neighbours((3, 3), neighbour)));
+
+        // corners of the bounding square are not part of the layout
+        assert!(!in_layout(&tiles, (0, 0)));
+        assert_eq!(neighbours(&tiles, (0, 2)).len(), 3);
+    }
+
+    #[test]
+    fn bases_have_six_neighbours_and_room_between_them() {
+        for map_size in MAP_SIZES {
+            let layout = map_size.layout();
+            let tiles = generate(map_size, MapGeneration::Random);
+            for (index, &base) in layout.base_positions.iter().enumerate() {
+                assert_eq!(
+                    neighbours(&tiles, base).len(),
+                    6,
+                    "{:?} {:?}",
+                    map_size,
+                    base
+                );
+                for &other in &layout.base_positions[index + 1..] {
+                    assert!(distance(base, other) >= 3, "{:?} {:?}", map_size, base);
+                }
+            }
+
+            let [first, second, ..] = layout.base_positions;
+            assert_eq!(mirror(first, layout.grid_size()), second);
+        }
+    }
+
+    #[test]
+    fn mirror_keeps_the_layout() {
+        for map_size in MAP_SIZES {
+            let grid_size = map_size.layout().grid_size();
+            let tiles = generate(map_size, MapGeneration::Random);
+            for row in 0..grid_size {
+                for col in 0..grid_size {
+                    let mirrored = mirror((row, col), grid_size);
+                    assert_eq!(in_layout(&tiles, (row, col)), in_layout(&tiles, mirrored));
+                    let mut expected = neighbours(&tiles, (row, col))
+                        .into_iter()
+                        .map(|neighbour| mirror(neighbour, grid_size))
+                        .collect::<Vec<_>>();
+                    let mut found = neighbours(&tiles, mirrored);
+                    expected.sort();
+                    found.sort();
+                    assert_eq!(found, expected, "{:?} {:?}", map_size, (row, col));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_maps_give_every_base_the_same_ring() {
+        for map_size in MAP_SIZES {
+            let layout = map_size.layout();
+            let tiles = generate(map_size, MapGeneration::Symmetric);
+            let ring = |base: Position| {
+                DIRECTIONS
+                    .iter()
+                    .map(|&direction| {
+                        let (q, r) = to_axial(base);
+                        let (row, col) = from_axial(q + direction.0, r + direction.1).unwrap();
+                        let tile = tiles[row][col].unwrap();
+                        (tile.level, tile.terrain)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            for &base in &layout.base_positions[1..] {
+                assert_eq!(ring(base), ring(layout.base_positions[0]), "{:?}", map_size);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_maps_mirror_away_from_the_bases() {
+        for map_size in MAP_SIZES {
+            let layout = map_size.layout();
+            let grid_size = layout.grid_size();
+            let tiles = generate(map_size, MapGeneration::Symmetric);
+            let near_base = |position: Position| {
+                layout
+                    .base_positions
+                    .iter()
+                    .any(|&base| distance(position, base) <= 1)
+            };
+
+            for row in 0..grid_size {
+                for col in 0..grid_size {
+                    let mirrored = mirror((row, col), grid_size);
+                    if near_base((row, col)) || !in_layout(&tiles, (row, col)) {
+                        continue;
+                    }
+                    let tile = tiles[row][col].unwrap();
+                    let mirrored_tile = tiles[mirrored.0][mirrored.1].unwrap();
+                    assert_eq!(tile.level, mirrored_tile.level, "{:?}", map_size);
+                    assert_eq!(tile.terrain, mirrored_tile.terrain, "{:?}", map_size);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn line_steps_through_neighbours() {
+        for (from, to) in [
+            ((0, 2), (6, 4)),
+            ((3, 0), (3, 6)),
+            ((1, 1), (5, 5)),
+            ((2, 4), (4, 1)),
+        ] {
+            let path = line(from, to);
+            assert_eq!(path.len(), distance(from, to) + 1);
+            assert_eq!(path.first(), Some(&from));
+            assert_eq!(path.last(), Some(&to));
+            assert!(path.windows(2).all(|pair| are_neighbours(pair[0], pair[1])));
+        }
+        assert_eq!(line((3, 3), (3, 3)), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn mountains_and_holes_block_line_of_sight() {
+        let mut tiles = generate(MapSize::Small, MapGeneration::Random);
+        for tile in tiles.iter_mut().flatten().flatten() {
+            tile.terrain = Terrain::Plains;
+        }
+        assert!(has_line_of_sight(&tiles, (3, 1), (3, 5)));
+        // neighbours always see each other
+        assert!(has_line_of_sight(&tiles, (3, 1), (3, 2)));
+        // outside the layout
+        assert!(!has_line_of_sight(&tiles, (3, 1), (0, 0)));
+
+        tiles[3][5].as_mut().unwrap().terrain = Terrain::Mountains;
+        assert!(has_line_of_sight(&tiles, (3, 1), (3, 5)));
+
+        tiles[3][3].as_mut().unwrap().terrain = Terrain::Mountains;
+        assert!(!has_line_of_sight(&tiles, (3, 1), (3, 5)));
+
+        tiles[3][3].as_mut().unwrap().terrain = Terrain::Plains;
+        tiles[3][4] = None;
+        assert!(!has_line_of_sight(&tiles, (3, 1), (3, 5)));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::errors::{ConstructionError, GameError};
-use crate::states::{Building, BuildingType, Game};
+use crate::states::{Building, BuildingType, Game, GameStatus, UnitType};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -19,6 +19,10 @@ pub fn build_construction(
     let game = &mut ctx.accounts.game;
     let player_pubkey = ctx.accounts.player.key();
 
+    if game.status != GameStatus::Live {
+        return err!(GameError::GameNotLive);
+    }
+
     if row >= game.tiles.len() || col >= game.tiles[0].len() {
         return err!(GameError::OutOfBounds);
     }
@@ -29,7 +33,7 @@ pub fn build_construction(
         .position(|player_option| {
             player_option
                 .as_ref()
-                .map_or(false, |player_info| player_info.pubkey == player_pubkey)
+                .is_some_and(|player_info| player_info.pubkey == player_pubkey)
         })
         .ok_or(GameError::InvalidPlayer)?;
 
@@ -47,6 +51,11 @@ pub fn build_construction(
         return err!(ConstructionError::NotYourTile);
     }
 
+    // buildings need ground units to reach them
+    if !tile.terrain.is_passable(UnitType::Infantry) {
+        return err!(ConstructionError::InvalidTerrain);
+    }
+
     let cost;
     if let Some(existing_building) = &mut tile.building {
         if existing_building.building_type != building_type {
@@ -66,7 +75,10 @@ pub fn build_construction(
         player_balance -= cost;
         existing_building.level += 1;
     } else {
-        if building_type == BuildingType::Base {
+        if matches!(
+            building_type,
+            BuildingType::Base | BuildingType::CapturedCapital
+        ) {
             return err!(ConstructionError::CannotBuildBase);
         }
 
@@ -0,0 +1,60 @@
+use crate::errors::GameError;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(mut, has_one = creator, close = creator)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = GameResult::LEN,
+        seeds = [b"RESULT", game.key().as_ref()],
+        bump
+    )]
+    pub game_result: Account<'info, GameResult>,
+
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+
+    /// CHECK: receives the rent of the closed game, validated by has_one
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let game_result = &mut ctx.accounts.game_result;
+
+    if game.status != GameStatus::Completed {
+        return err!(GameError::GameNotCompleted);
+    }
+
+    // Player stats and active games are updated by settle_game
+    if !game.is_settled {
+        return err!(GameError::GameNotSettled);
+    }
+
+    game_result.game = game.key();
+    game_result.game_id = game.game_id;
+    game_result.creator = game.creator;
+    game_result.players = game.players;
+    game_result.winner = game.winner;
+    game_result.outcome = game.outcome;
+    game_result.is_multiplayer = game.is_multiplayer;
+    game_result.round = game.round;
+    game_result.seed = game.seed;
+    game_result.closed_at = Clock::get()?.unix_timestamp;
+
+    // normally gone since the game started, kept here so no closed game stays listed
+    ctx.accounts.lobby_registry.remove(&game.key());
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
 use crate::errors::GameError;
+use crate::hex::{self, Position};
+use crate::instructions::game_index::init_active_entry;
+use crate::instructions::lobby::update_lobby_listing;
 use crate::states::*;
 use anchor_lang::solana_program::hash::{hashv, Hash};
 
@@ -6,7 +9,7 @@ use anchor_lang::prelude::*;
 
 // pre-generated bot public keys to save CU
 // neutral NPCs have Pubkey::default()
-const BOT_PUBLIC_KEYS: [Pubkey; 3] = [
+pub const BOT_PUBLIC_KEYS: [Pubkey; 3] = [
     Pubkey::new_from_array([
         8, 234, 193, 188, 163, 128, 181, 240, 180, 42, 49, 19, 218, 104, 195, 108, 189, 69, 131,
         163, 197, 198, 186, 4, 166, 41, 174, 72, 173, 229, 125, 207,
@@ -22,13 +25,19 @@ const BOT_PUBLIC_KEYS: [Pubkey; 3] = [
 ];
 
 #[derive(Accounts)]
+#[instruction(settings: GameSettings)]
 pub struct CreateGame<'info> {
     #[account(mut, seeds = [b"SUPER"], bump)]
     pub super_state: Account<'info, SuperState>,
-    #[account(mut, seeds = [b"PROFILE", player.key().as_ref()], bump, has_one = player)]
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+    #[account(seeds = [b"PROFILE", player.key().as_ref()], bump, has_one = player)]
     pub creator_profile: Account<'info, PlayerProfile>,
-    #[account(init, payer = player, space = Game::LEN, seeds = [b"GAME", &super_state.game_count.to_le_bytes()], bump)]
+    pub map_template: Option<Account<'info, MapTemplate>>,
+    #[account(init, payer = player, space = Game::space(settings.map_size, map_template.as_deref()), seeds = [b"GAME", &super_state.game_count.to_le_bytes()], bump)]
     pub game: Account<'info, Game>,
+    #[account(init, payer = player, space = GameIndexEntry::LEN, seeds = [b"ACTIVE_GAME", creator_profile.key().as_ref(), game.key().as_ref()], bump)]
+    pub active_game_entry: Account<'info, GameIndexEntry>,
     #[account(mut)]
     pub player: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -39,114 +48,169 @@ pub struct JoinGame<'info> {
     #[account(mut)]
     pub game: Account<'info, Game>,
 
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+
     #[account(mut)]
     pub player: Signer<'info>,
 
     #[account(
-        mut,
         seeds = [b"PROFILE", player.key().as_ref()],
         bump,
         has_one = player
     )]
     pub player_profile: Account<'info, PlayerProfile>,
 
+    #[account(
+        init,
+        payer = player,
+        space = GameIndexEntry::LEN,
+        seeds = [b"ACTIVE_GAME", player_profile.key().as_ref(), game.key().as_ref()],
+        bump
+    )]
+    pub active_game_entry: Account<'info, GameIndexEntry>,
+
+    // Keypair derived from the join code, only for games with a join code
+    pub join_code_signer: Option<Signer<'info>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn create_game(
     ctx: Context<CreateGame>,
-    max_players: u8,
-    is_multiplayer: bool,
-    map_size: MapSize,
+    settings: GameSettings,
+    access: GameAccess,
+    seed: Option<[u8; 32]>,
+    commitment: Option<[u8; 32]>,
 ) -> Result<()> {
-    let super_state = &mut ctx.accounts.super_state;
-    let game = &mut ctx.accounts.game;
-    let creator_profile = &mut ctx.accounts.creator_profile;
+    validate_settings(&settings)?;
 
-    if creator_profile.active_games.len() >= PlayerProfile::MAX_ACTIVE_GAMES {
-        // TODO: remove this once frontend will allow to manage all active games
-        creator_profile.active_games.remove(0);
-        // return err!(GameError::TooManyActiveGames);
+    if let GameAccess::InviteOnly { invited } = &access {
+        if invited.len() > GameAccess::MAX_INVITES {
+            return err!(GameError::TooManyInvites);
+        }
     }
-    creator_profile.active_games.push(game.key());
+
+    let super_state = &mut ctx.accounts.super_state;
+    let game = &mut ctx.accounts.game;
 
     let game_id = super_state.game_count;
     super_state.game_count += 1;
 
-    game.game_id = game_id;
-    game.creator = ctx.accounts.player.key();
-    game.status = if is_multiplayer {
-        GameStatus::NotStarted
-    } else {
-        GameStatus::Live
+    // Multiplayer maps are seeded by commit-reveal so no single player picks the map,
+    // explicit seeds only replay known maps against bots. Templates don't use the seed.
+    if settings.human_seats > 1 && seed.is_some() {
+        return err!(GameError::ExplicitSeedNotAllowed);
+    }
+    let commit_reveal = settings.human_seats > 1 && ctx.accounts.map_template.is_none();
+    let seed = match seed {
+        Some(seed) => seed,
+        None if commit_reveal => [0; 32],
+        None => derive_seed(&game.key())?,
     };
-    game.max_players = max_players;
-    game.is_multiplayer = is_multiplayer;
-    game.map_size = map_size;
-    game.round = 1;
-    game.current_player_index = 0;
-    game.turn_time_limit = 60;
-    game.turn_timestamp = if is_multiplayer {
-        Clock::get().unwrap().unix_timestamp as u64
+
+    setup_game(
+        game,
+        game_id,
+        ctx.accounts.player.key(),
+        settings,
+        access,
+        seed,
+    );
+    let is_multiplayer = game.is_multiplayer;
+
+    // the terrain is known from the start, bases are handed out when the game starts
+    if let Some(map_template) = &ctx.accounts.map_template {
+        game.map_template = Some(map_template.key());
+        game.tiles = map_template.build_tiles();
+        for position in map_template.bases_for(settings.total_players()) {
+            if let Some(tile) = &mut game.tiles[position.row as usize][position.col as usize] {
+                tile.units = None;
+                tile.building = Some(Building {
+                    building_type: BuildingType::Base,
+                    level: 1,
+                });
+            }
+        }
+    }
+
+    if commit_reveal {
+        game.commit_reveal = true;
+        if let Some(creator_info) = game.players[0].as_mut() {
+            creator_info.commitment = Some(commitment.ok_or(GameError::MissingCommitment)?);
+        }
+    }
+
+    init_active_entry(
+        &mut ctx.accounts.active_game_entry,
+        ctx.accounts.player.key(),
+        game,
+    )?;
+
+    // Single player games start right away, multiplayer ones wait in the lobby
+    if !is_multiplayer {
+        launch_game(game)?;
     } else {
-        0
-    };
+        update_lobby_listing(&mut ctx.accounts.lobby_registry, game.key(), game)?;
+    }
 
-    let total_players = game.max_players as usize;
-    let humans = if game.is_multiplayer { 2 } else { 1 };
-    let num_bots = total_players - humans;
+    Ok(())
+}
 
-    game.players = [None; Game::MAX_PLAYERS];
+pub fn validate_settings(settings: &GameSettings) -> Result<()> {
+    if settings.human_seats == 0 {
+        return err!(GameError::InvalidHumanSeats);
+    }
 
-    let mut player_infos = Vec::with_capacity(total_players);
+    if settings.bot_seats as usize > BOT_PUBLIC_KEYS.len() {
+        return err!(GameError::TooManyBots);
+    }
 
-    game.players[0] = Some(PlayerInfo {
-        pubkey: ctx.accounts.player.key(),
-        is_bot: false,
-        balance: 2,
-        attack_points: 1,
-        is_alive: true,
-    });
-    player_infos.push(PlayerInfo {
-        pubkey: ctx.accounts.player.key(),
-        is_bot: false,
-        balance: 2,
-        attack_points: 1,
-        is_alive: true,
-    });
+    // every map size and template has bases for up to MAX_PLAYERS
+    let total_players = settings.total_players();
+    if !(2..=Game::MAX_PLAYERS).contains(&total_players) {
+        return err!(GameError::InvalidPlayerCount);
+    }
 
-    // Add bots if any
-    for i in 1..=num_bots {
-        let bot_pubkey = BOT_PUBLIC_KEYS
-            .get(i - 1)
-            .ok_or(GameError::BotKeyNotFound)?;
-        let bot_info = PlayerInfo {
-            pubkey: *bot_pubkey,
-            is_bot: true,
-            balance: 2,
-            attack_points: 1,
-            is_alive: true,
-        };
-        game.players[i] = Some(bot_info);
-        player_infos.push(bot_info);
+    if !(Game::MIN_TURN_TIME_LIMIT..=Game::MAX_TURN_TIME_LIMIT).contains(&settings.turn_time_limit)
+    {
+        return err!(GameError::InvalidTurnTimeLimit);
     }
 
-    if !is_multiplayer {
-        game.tiles = initialize_tiles(&game.key(), &player_infos, &game.map_size)?;
+    if settings.starting_balance > GameSettings::MAX_STARTING_BALANCE {
+        return err!(GameError::InvalidStartingBalance);
+    }
+
+    if !(1..=GameSettings::MAX_STARTING_UNITS).contains(&settings.starting_units) {
+        return err!(GameError::InvalidStartingUnits);
+    }
+
+    if settings.starting_attack_points > Game::MAX_ATTACK_POINTS {
+        return err!(GameError::InvalidStartingAttackPoints);
     }
 
     Ok(())
 }
 
-pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
+pub fn join_game(ctx: Context<JoinGame>, commitment: Option<[u8; 32]>) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let player = &ctx.accounts.player;
-    let player_profile = &mut ctx.accounts.player_profile;
 
     if game.status != GameStatus::NotStarted {
         return err!(GameError::GameAlreadyStarted);
     }
 
+    if game.kicked.contains(&player.key()) {
+        return err!(GameError::PlayerKicked);
+    }
+
+    let join_code_signer = ctx
+        .accounts
+        .join_code_signer
+        .as_ref()
+        .map(|signer| signer.key());
+    check_access(&game.access, &player.key(), join_code_signer.as_ref())?;
+
     let already_joined = game.players.iter().any(|player_info_option| {
         if let Some(player_info) = player_info_option {
             player_info.pubkey == player.key()
@@ -160,27 +224,26 @@ pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
     }
 
     let num_players = game.players.iter().filter(|p| p.is_some()).count();
-    if num_players >= game.max_players as usize {
+    if num_players >= game.settings.human_seats as usize {
         return err!(GameError::GameIsFull);
     }
 
-    if player_profile.active_games.len() >= PlayerProfile::MAX_ACTIVE_GAMES {
-        // TODO: fix me. Remove the oldest game if player got 10 active games
-        player_profile.active_games.remove(0);
-    }
-    player_profile.active_games.push(game.key());
+    init_active_entry(&mut ctx.accounts.active_game_entry, player.key(), game)?;
+
+    let commitment = if game.commit_reveal {
+        Some(commitment.ok_or(GameError::MissingCommitment)?)
+    } else {
+        None
+    };
 
     // Find the next available slot in the players array
+    let settings = game.settings;
     let mut added = false;
     for player_slot in game.players.iter_mut() {
         if player_slot.is_none() {
-            *player_slot = Some(PlayerInfo {
-                pubkey: player.key(),
-                is_bot: false,
-                balance: 2,
-                attack_points: 1,
-                is_alive: true,
-            });
+            let mut player_info = PlayerInfo::new(player.key(), false, &settings);
+            player_info.commitment = commitment;
+            *player_slot = Some(player_info);
             added = true;
             break;
         }
@@ -190,79 +253,207 @@ pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
         return err!(GameError::GameIsFull);
     }
 
-    // start the game if the number of players is now equal to max_players
-    let num_players = game.players.iter().filter(|p| p.is_some()).count();
-    if num_players == game.max_players as usize {
-        game.status = GameStatus::Live;
+    // the game waits in the lobby until the creator starts it, unlisted once it's full
+    update_lobby_listing(&mut ctx.accounts.lobby_registry, game.key(), game)?;
 
-        let player_infos: Vec<PlayerInfo> = game.players.iter().filter_map(|p| *p).collect();
-        game.tiles = initialize_tiles(&game.key(), &player_infos, &game.map_size)?;
+    Ok(())
+}
+
+fn check_access(
+    access: &GameAccess,
+    player: &Pubkey,
+    join_code_signer: Option<&Pubkey>,
+) -> Result<()> {
+    match access {
+        GameAccess::Public => Ok(()),
+        GameAccess::InviteOnly { invited } => {
+            if !invited.contains(player) {
+                return err!(GameError::NotInvited);
+            }
+            Ok(())
+        }
+        GameAccess::JoinCode { code_signer } => {
+            if join_code_signer != Some(code_signer) {
+                return err!(GameError::InvalidJoinCode);
+            }
+            Ok(())
+        }
     }
+}
+
+// Sets up a game that is not started yet, with the creator in the first seat
+pub fn setup_game(
+    game: &mut Game,
+    game_id: u32,
+    creator: Pubkey,
+    settings: GameSettings,
+    access: GameAccess,
+    seed: [u8; 32],
+) {
+    let is_multiplayer = settings.human_seats > 1;
+
+    game.game_id = game_id;
+    game.creator = creator;
+    game.status = GameStatus::NotStarted;
+    game.settings = settings;
+    game.access = access;
+    game.kicked = Vec::new();
+    game.seed = seed;
+    game.map_template = None;
+    game.commit_reveal = false;
+    game.reveal_deadline = 0;
+    game.is_multiplayer = is_multiplayer;
+    game.round = 1;
+    game.current_player_index = 0;
+    game.turn_timestamp = if is_multiplayer {
+        Clock::get().unwrap().unix_timestamp as u64
+    } else {
+        0
+    };
+
+    game.players = [None; Game::MAX_PLAYERS];
+    game.players[0] = Some(PlayerInfo::new(creator, false, &settings));
+}
+
+// Seats the bots in the remaining slots and generates the map.
+// Human seats still open when the game starts are taken by bots as well.
+pub fn launch_game(game: &mut Account<Game>) -> Result<()> {
+    let settings = game.settings;
+    let mut bot_keys = BOT_PUBLIC_KEYS.iter();
+
+    for player_slot in game.players.iter_mut().take(settings.total_players()) {
+        if player_slot.is_none() {
+            if let Some(bot_pubkey) = bot_keys.next() {
+                *player_slot = Some(PlayerInfo::new(*bot_pubkey, true, &settings));
+            }
+        }
+    }
+
+    game.status = GameStatus::Live;
+
+    let player_infos: Vec<PlayerInfo> = game.players.iter().filter_map(|p| *p).collect();
+    game.tiles = match game.map_template {
+        Some(_) => {
+            let mut tiles = std::mem::take(&mut game.tiles);
+            assign_template_bases(&mut tiles, &player_infos, &settings);
+            tiles
+        }
+        None => initialize_tiles(&game.seed, &player_infos, &settings),
+    };
 
     Ok(())
 }
 
-fn initialize_tiles(
-    game_pubkey: &Pubkey,
+// Template bases are handed out in row order
+fn assign_template_bases(
+    tiles: &mut [Vec<Option<Tile>>],
     player_infos: &[PlayerInfo],
-    map_size: &MapSize,
-) -> Result<Vec<Vec<Option<Tile>>>> {
-    let layout = Game::get_map_layout(map_size.clone());
-    let grid_size = layout.len();
+    settings: &GameSettings,
+) {
+    let base_tiles = tiles.iter_mut().flatten().flatten().filter(|tile| {
+        tile.building
+            .is_some_and(|building| building.building_type == BuildingType::Base)
+    });
+
+    for (tile, player_info) in base_tiles.zip(player_infos.iter()) {
+        place_base(tile, player_info, settings);
+    }
+}
+
+fn place_base(tile: &mut Tile, player_info: &PlayerInfo, settings: &GameSettings) {
+    tile.owner = player_info.pubkey;
+    tile.level = 1;
+    tile.terrain = Terrain::Plains;
+    tile.units = Some(Units {
+        unit_type: UnitType::Infantry,
+        quantity: settings.starting_units,
+        stamina: 1,
+    });
+    tile.building = Some(Building {
+        building_type: BuildingType::Base,
+        level: 1,
+    });
+}
+
+pub fn derive_seed(game_pubkey: &Pubkey) -> Result<[u8; 32]> {
+    let slot = Clock::get()?.slot;
+    Ok(hashv(&[game_pubkey.as_ref(), &slot.to_le_bytes()]).to_bytes())
+}
+
+// Pure, so clients can rebuild the map of any game from its seed, players and settings
+pub fn initialize_tiles(
+    seed: &[u8; 32],
+    player_infos: &[PlayerInfo],
+    settings: &GameSettings,
+) -> Vec<Vec<Option<Tile>>> {
+    let layout = settings.map_size.layout();
+    let grid_size = layout.grid_size();
 
     // Initialize empty grid
     let mut grid: Vec<Vec<Option<Tile>>> = vec![vec![None; grid_size]; grid_size];
 
-    let clock = Clock::get().unwrap();
-    let slot = clock.slot;
-
-    let base_positions = get_base_positions(map_size);
+    let base_positions = layout.base_positions;
 
     let mut base_tile_to_player = std::collections::HashMap::new();
     for (player_info, &(row, col)) in player_infos.iter().zip(base_positions.iter()) {
         base_tile_to_player.insert((row, col), player_info);
     }
 
-    for (row_index, &tiles_in_row) in layout.iter().enumerate() {
+    for (row_index, &tiles_in_row) in layout.row_widths.iter().enumerate() {
         let tiles_in_row = tiles_in_row as usize;
         let empty_spaces = (grid_size - tiles_in_row) / 2;
 
         for col_index in 0..tiles_in_row {
             let adjusted_col = col_index + empty_spaces;
 
-            let tile_index = row_index * grid_size + adjusted_col;
-            let level = get_random_tile_level(game_pubkey, tile_index, slot);
-            let mut tile = Tile::new(level);
+            let position = (row_index, adjusted_col);
+            let (level_row, level_col) = match settings.map_generation {
+                MapGeneration::Random => position,
+                MapGeneration::Symmetric => symmetric_cell(position, &base_positions, grid_size),
+            };
+            let tile_index = level_row * grid_size + level_col;
+            let level = get_random_tile_level(seed, tile_index);
+            // bases start on open ground so the first moves cost the same for everyone
+            let near_base = base_positions
+                .iter()
+                .any(|&base| hex::distance(position, base) <= 1);
+            let terrain = match get_random_terrain(seed, tile_index) {
+                Terrain::Ruins => Terrain::Ruins,
+                _ if near_base => Terrain::Plains,
+                terrain => terrain,
+            };
+            let mut tile = Tile::new(level, terrain);
 
             // initalize base
             if let Some(player_info) = base_tile_to_player.get(&(row_index, adjusted_col)) {
-                tile.owner = player_info.pubkey;
-                tile.level = 1;
-                tile.units = Some(Units {
-                    unit_type: UnitType::Infantry,
-                    quantity: 5,
-                    stamina: 1,
-                });
-                tile.building = Some(Building {
-                    building_type: BuildingType::Base,
-                    level: 1,
-                });
+                place_base(&mut tile, player_info, settings);
             }
 
             grid[row_index][adjusted_col] = Some(tile);
         }
     }
 
-    Ok(grid)
+    grid
+}
+
+// Picks the cell whose level and terrain a tile copies. The neighbours of every base
+// copy the neighbours of the first base, so all bases start with the same surroundings.
+// The rest of the map is folded by the hex symmetry of the layout.
+fn symmetric_cell(position: Position, base_positions: &[Position], grid_size: usize) -> Position {
+    for &base in base_positions {
+        if hex::distance(position, base) <= 1 {
+            if let Some(cell) = hex::translate(position, base, base_positions[0]) {
+                return cell;
+            }
+        }
+    }
+
+    position.min(hex::mirror(position, grid_size))
 }
 
 // 40% chance of level 1, 40% chance of level 2, 20% chance of level 3
-fn get_random_tile_level(game_pubkey: &Pubkey, tile_index: usize, slot: u64) -> u8 {
-    let seed_data = &[
-        game_pubkey.as_ref(),
-        &tile_index.to_le_bytes(),
-        &slot.to_le_bytes(),
-    ];
+pub fn get_random_tile_level(seed: &[u8; 32], tile_index: usize) -> u8 {
+    let seed_data = &[seed.as_ref(), &(tile_index as u64).to_le_bytes()];
 
     let hash_result: Hash = hashv(seed_data);
     let random_number = hash_result.as_ref()[0] % 100 + 1;
@@ -275,9 +466,23 @@ fn get_random_tile_level(game_pubkey: &Pubkey, tile_index: usize, slot: u64) ->
     }
 }
 
-fn get_base_positions(map_size: &MapSize) -> Vec<(usize, usize)> {
-    match map_size {
-        MapSize::Small => vec![(1, 1), (1, 5), (5, 1), (5, 5)],
-        MapSize::Large => vec![(0, 4), (4, 0), (4, 8), (8, 4)],
+// 45% plains, 15% hills, 15% forest, 8% mountains, 8% water, 9% ruins
+pub fn get_random_terrain(seed: &[u8; 32], tile_index: usize) -> Terrain {
+    let seed_data = &[
+        seed.as_ref(),
+        &(tile_index as u64).to_le_bytes(),
+        b"TERRAIN",
+    ];
+
+    let hash_result: Hash = hashv(seed_data);
+    let random_number = hash_result.as_ref()[0] % 100 + 1;
+
+    match random_number {
+        1..=45 => Terrain::Plains,
+        46..=60 => Terrain::Hills,
+        61..=75 => Terrain::Forest,
+        76..=83 => Terrain::Mountains,
+        84..=91 => Terrain::Water,
+        _ => Terrain::Ruins,
     }
 }
@@ -1,11 +1,11 @@
 use crate::ai::process_bot_turn;
 use crate::errors::GameError;
+use crate::events::PlayerAfk;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
 const MAX_PLAYERS: usize = Game::MAX_PLAYERS;
 const MAX_ATTACK_POINTS: u8 = Game::MAX_ATTACK_POINTS;
-const MAX_TURN_DURATION: u64 = 60;
 
 #[derive(Accounts)]
 pub struct EndTurn<'info> {
@@ -16,23 +16,29 @@ pub struct EndTurn<'info> {
 }
 
 pub fn end_turn(ctx: Context<EndTurn>) -> Result<()> {
+    let game_key = ctx.accounts.game.key();
     let game = &mut ctx.accounts.game;
     let current_timestamp = Clock::get()?.unix_timestamp as u64;
 
+    if game.status != GameStatus::Live {
+        return err!(GameError::GameNotLive);
+    }
+
     let player_pubkey = ctx.accounts.player.key();
     let current_player_info = game.players[game.current_player_index as usize]
         .as_ref()
         .ok_or(GameError::InvalidPlayer)?;
 
-    let time_elapsed = current_timestamp.saturating_sub(game.turn_timestamp);
+    let timed_out = current_player_info.pubkey != player_pubkey;
 
-    if current_player_info.pubkey != player_pubkey && time_elapsed < MAX_TURN_DURATION {
+    if timed_out && !game.is_turn_expired(current_timestamp) {
         return err!(GameError::NotYourTurn);
     }
 
     if !game.is_multiplayer {
         process_single_player_turn(game)?;
     } else {
+        track_missed_turns(game, game_key, timed_out)?;
         process_multiplayer_turn(game)?;
     }
 
@@ -41,17 +47,49 @@ pub fn end_turn(ctx: Context<EndTurn>) -> Result<()> {
     Ok(())
 }
 
-fn process_single_player_turn(game: &mut Game) -> Result<()> {
-    process_bot_turns(game)?;
+// Someone else ending the turn means the current player let the timer run out
+fn track_missed_turns(game: &mut Game, game_key: Pubkey, timed_out: bool) -> Result<()> {
+    let afk_policy = game.settings.afk_policy;
 
-    let (player_pubkeys, mut incomes) = init_incomes(game);
-    calculate_incomes(game, &mut incomes, &player_pubkeys)?;
+    let player_info = game.players[game.current_player_index as usize]
+        .as_mut()
+        .ok_or(GameError::InvalidPlayer)?;
 
-    apply_incomes(game, &incomes, MAX_ATTACK_POINTS)?;
+    if !timed_out {
+        player_info.missed_turns = 0;
+        return Ok(());
+    }
 
-    remove_defeated_players(game)?;
+    player_info.missed_turns = player_info.missed_turns.saturating_add(1);
 
-    game.round += 1;
+    if afk_policy.threshold == 0 || player_info.missed_turns < afk_policy.threshold {
+        return Ok(());
+    }
+
+    let player_pubkey = player_info.pubkey;
+    let missed_turns = player_info.missed_turns;
+
+    match afk_policy.action {
+        AfkAction::Eliminate => game.eliminate_player(player_pubkey, None),
+        AfkAction::ReplaceWithBot => player_info.is_bot = true,
+    }
+
+    emit!(PlayerAfk {
+        game: game_key,
+        player: player_pubkey,
+        missed_turns,
+        action: afk_policy.action,
+    });
+
+    Ok(())
+}
+
+fn process_single_player_turn(game: &mut Game) -> Result<()> {
+    process_bot_turns(game)?;
+    end_round(game)?;
+
+    remove_defeated_players(game)?;
+    check_victory_conditions(game)?;
 
     Ok(())
 }
@@ -124,35 +162,57 @@ fn apply_incomes(game: &mut Game, incomes: &[u32], max_attack_points: u8) -> Res
 }
 
 fn process_multiplayer_turn(game: &mut Game) -> Result<()> {
+    advance_turn(game)?;
+
+    remove_defeated_players(game)?;
+    check_victory_conditions(game)?;
+
+    Ok(())
+}
+
+// Passes the turn to the next human player who is still in the game.
+// Bots seated in between play their turns, incomes are applied once per round.
+pub fn advance_turn(game: &mut Game) -> Result<()> {
     let num_players = game.players.len();
+    let current_index = game.current_player_index as usize;
 
     for i in 1..=num_players {
-        let next_index = (game.current_player_index as usize + i) % num_players;
-        if let Some(player_info) = &game.players[next_index] {
-            if !player_info.is_bot {
+        let next_index = (current_index + i) % num_players;
+
+        // wrapped around the turn order
+        if next_index == 0 {
+            end_round(game)?;
+        }
+
+        match game.players[next_index] {
+            Some(player_info) if player_info.is_alive && player_info.is_bot => {
+                process_bot_turn(game, next_index)?;
+            }
+            Some(player_info) if player_info.is_alive => {
                 game.current_player_index = next_index as u8;
                 break;
             }
+            _ => {}
         }
     }
 
-    if game.current_player_index == 0 {
-        game.round += 1;
-    }
+    Ok(())
+}
 
+fn end_round(game: &mut Game) -> Result<()> {
     let (player_pubkeys, mut incomes) = init_incomes(game);
     calculate_incomes(game, &mut incomes, &player_pubkeys)?;
 
     apply_incomes(game, &incomes, MAX_ATTACK_POINTS)?;
 
-    remove_defeated_players(game)?;
+    game.round += 1;
 
     Ok(())
 }
 
 use std::collections::HashMap;
 
-fn remove_defeated_players(game: &mut Game) -> Result<()> {
+pub fn remove_defeated_players(game: &mut Game) -> Result<()> {
     let mut player_alive_status: HashMap<Pubkey, bool> = HashMap::new();
 
     for player_info in game.players.iter().flatten() {
@@ -173,42 +233,508 @@ fn remove_defeated_players(game: &mut Game) -> Result<()> {
         }
     }
 
-    for player_info in game.players.iter_mut().flatten() {
-        if let Some(&is_alive) = player_alive_status.get(&player_info.pubkey) {
-            player_info.is_alive = is_alive;
-        }
+    // Owning a Base never revives a player who was already eliminated
+    let defeated_players: Vec<Pubkey> = game
+        .players
+        .iter()
+        .flatten()
+        .filter(|player_info| player_alive_status.get(&player_info.pubkey) == Some(&false))
+        .map(|player_info| player_info.pubkey)
+        .collect();
+
+    for player_pubkey in defeated_players {
+        game.eliminate_player(player_pubkey, None);
     }
 
-    // Completely remove all tiles of defeated players from the grid
-    // TODO: looks fun, but in some edge cases it's not possible to continue without planes
+    let conquerors: HashMap<Pubkey, Option<Pubkey>> = game
+        .players
+        .iter()
+        .flatten()
+        .filter(|player_info| !player_info.is_alive)
+        .map(|player_info| (player_info.pubkey, player_info.eliminated_by))
+        .collect();
+
+    let elimination_rule = game.settings.elimination_rule;
+
     for row in &mut game.tiles {
         for tile_option in row.iter_mut() {
             if let Some(tile) = tile_option {
-                if let Some(&is_alive) = player_alive_status.get(&tile.owner) {
-                    if !is_alive {
-                        *tile_option = None;
+                if let Some(&conqueror) = conquerors.get(&tile.owner) {
+                    match (elimination_rule, conqueror) {
+                        // Completely remove all tiles of defeated players from the grid
+                        // TODO: looks fun, but in some edge cases it's not possible to continue without planes
+                        (EliminationRule::RemoveTiles, _) => {
+                            *tile_option = None;
+                        }
+                        (EliminationRule::TransferToConqueror, Some(conqueror)) => {
+                            tile.capture_capital(conqueror);
+                            tile.owner = conqueror;
+                            tile.units = None;
+                        }
+                        // Players who left the game without a conqueror fall back to neutral
+                        (EliminationRule::NeutralTiles, _)
+                        | (EliminationRule::TransferToConqueror, None) => {
+                            if let Some(Building {
+                                building_type: BuildingType::Base,
+                                ..
+                            }) = tile.building
+                            {
+                                tile.building = None;
+                            }
+                            tile.owner = Pubkey::default();
+                            let mutants = Tile::default_mutants(tile.level, tile.terrain);
+                            tile.units = (mutants > 0).then_some(Units {
+                                unit_type: UnitType::Mutants,
+                                quantity: mutants,
+                                stamina: 0,
+                            });
+                        }
                     }
                 }
             }
         }
     }
 
-    let alive_players: Vec<&PlayerInfo> = game
+    Ok(())
+}
+
+pub fn check_victory_conditions(game: &mut Game) -> Result<()> {
+    if game.status == GameStatus::Completed {
+        return Ok(());
+    }
+
+    let alive_players: Vec<Pubkey> = game
         .players
         .iter()
         .filter_map(|player_option| player_option.as_ref())
         .filter(|player| player.is_alive)
+        .map(|player| player.pubkey)
         .collect();
 
-    let alive_count = alive_players.len();
+    let humans_alive = game
+        .players
+        .iter()
+        .flatten()
+        .any(|player| !player.is_bot && player.is_alive);
+
+    match alive_players[..] {
+        [] => {
+            // e.g. mutual wipe on the last Base
+            game.complete(None, GameOutcome::Draw);
+            return Ok(());
+        }
+        [winner] => {
+            game.complete(Some(winner), GameOutcome::Elimination);
+            return Ok(());
+        }
+        _ => {}
+    }
 
-    if alive_count <= 1 {
-        game.status = GameStatus::Completed;
+    // Bots don't play on their own once every human is out
+    if !humans_alive {
+        game.complete(None, GameOutcome::Abandoned);
+        return Ok(());
     }
 
-    if alive_count == 1 {
-        game.winner = Some(alive_players[0].pubkey);
+    let live_tiles = game.count_live_tiles();
+
+    for &player_pubkey in &alive_players {
+        let owned_tiles = game.count_owned_tiles(&player_pubkey);
+        if live_tiles > 0 && owned_tiles * 100 >= live_tiles * Game::TERRITORY_VICTORY_PERCENT {
+            game.complete(Some(player_pubkey), GameOutcome::Territory);
+            return Ok(());
+        }
+    }
+
+    let max_rounds = game.settings.max_rounds;
+    if max_rounds > 0 && game.round > max_rounds {
+        settle_on_score(game, &alive_players);
     }
 
     Ok(())
 }
+
+// Player with the most tiles wins once the round limit is reached, a tie is a draw
+fn settle_on_score(game: &mut Game, alive_players: &[Pubkey]) {
+    let scores: Vec<(Pubkey, u32)> = alive_players
+        .iter()
+        .map(|player_pubkey| (*player_pubkey, game.count_owned_tiles(player_pubkey)))
+        .collect();
+
+    let best_score = scores.iter().map(|&(_, score)| score).max().unwrap_or(0);
+    let leaders: Vec<Pubkey> = scores
+        .iter()
+        .filter(|&&(_, score)| score == best_score)
+        .map(|&(player_pubkey, _)| player_pubkey)
+        .collect();
+
+    match leaders[..] {
+        [winner] => game.complete(Some(winner), GameOutcome::Score),
+        _ => game.complete(None, GameOutcome::Draw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::initialize_tiles;
+
+    // Live multiplayer game on the small map, the seats listed in `bots` are bots
+    fn live_game(player_count: u8, bots: &[usize]) -> Game {
+        let settings = GameSettings::matchmaking(MapSize::Small, player_count);
+        let mut players = [None; MAX_PLAYERS];
+        for (index, seat) in players.iter_mut().take(player_count as usize).enumerate() {
+            let is_bot = bots.contains(&index);
+            *seat = Some(PlayerInfo::new(Pubkey::new_unique(), is_bot, &settings));
+        }
+        let player_infos: Vec<PlayerInfo> = players.iter().flatten().copied().collect();
+        let seed = [1; 32];
+
+        Game {
+            game_id: 1,
+            creator: player_infos[0].pubkey,
+            players,
+            winner: None,
+            outcome: None,
+            status: GameStatus::Live,
+            settings,
+            access: GameAccess::Public,
+            kicked: Vec::new(),
+            seed,
+            map_template: None,
+            commit_reveal: false,
+            reveal_deadline: 0,
+            is_multiplayer: true,
+            round: 1,
+            current_player_index: 0,
+            turn_timestamp: 0,
+            eliminated_count: 0,
+            is_settled: false,
+            tiles: initialize_tiles(&seed, &player_infos, &settings),
+        }
+    }
+
+    fn pubkey(game: &Game, index: usize) -> Pubkey {
+        game.players[index].unwrap().pubkey
+    }
+
+    // Hands `count` neutral tiles without a building to `owner`
+    fn give_tiles(game: &mut Game, owner: Pubkey, count: usize) {
+        game.tiles
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .filter(|tile| tile.is_neutral() && tile.building.is_none())
+            .take(count)
+            .for_each(|tile| tile.owner = owner);
+    }
+
+    #[test]
+    fn territory_needs_51_percent_of_live_tiles() {
+        let mut game = live_game(2, &[]);
+        let player = pubkey(&game, 0);
+        assert_eq!(game.count_live_tiles(), 37);
+
+        // base and 17 more tiles, 18 of 37 is below 51%
+        give_tiles(&mut game, player, 17);
+        check_victory_conditions(&mut game).unwrap();
+        assert!(game.status == GameStatus::Live);
+
+        give_tiles(&mut game, player, 1);
+        check_victory_conditions(&mut game).unwrap();
+        assert!(game.status == GameStatus::Completed);
+        assert_eq!(game.winner, Some(player));
+        assert_eq!(game.outcome, Some(GameOutcome::Territory));
+    }
+
+    #[test]
+    fn holes_do_not_count_towards_territory() {
+        let mut game = live_game(2, &[]);
+        let player = pubkey(&game, 0);
+        game.tiles[0][2] = None;
+        game.tiles[0][3] = None;
+
+        // 18 of 35 live tiles is enough
+        give_tiles(&mut game, player, 17);
+        check_victory_conditions(&mut game).unwrap();
+        assert_eq!(game.winner, Some(player));
+        assert_eq!(game.outcome, Some(GameOutcome::Territory));
+    }
+
+    // Player 0 destroys the Base of player 1, who owns `tiles` more tiles
+    fn capture_second_base(elimination_rule: EliminationRule, tiles: usize) -> Game {
+        let mut game = live_game(3, &[]);
+        game.settings.elimination_rule = elimination_rule;
+        let (conqueror, defeated) = (pubkey(&game, 0), pubkey(&game, 1));
+        give_tiles(&mut game, defeated, tiles);
+
+        let (row, col) = game.settings.map_size.layout().base_positions[1];
+        let base = game.tiles[row][col].as_mut().unwrap();
+        assert_eq!(base.capture_capital(conqueror), Some(defeated));
+        game.eliminate_player(defeated, Some(conqueror));
+
+        remove_defeated_players(&mut game).unwrap();
+        game
+    }
+
+    #[test]
+    fn captured_base_becomes_a_captured_capital() {
+        let game = capture_second_base(EliminationRule::RemoveTiles, 0);
+        let (row, col) = game.settings.map_size.layout().base_positions[1];
+        let tile = game.tiles[row][col].unwrap();
+        assert_eq!(tile.owner, pubkey(&game, 0));
+        assert!(tile.building.unwrap().building_type == BuildingType::CapturedCapital);
+
+        let defeated = game.players[1].unwrap();
+        assert!(!defeated.is_alive);
+        assert_eq!(defeated.eliminated_by, Some(pubkey(&game, 0)));
+        assert_eq!(defeated.elimination_order, 1);
+
+        // only a Base can be captured
+        let mut plain = Tile::new(1, Terrain::Plains);
+        assert_eq!(plain.capture_capital(pubkey(&game, 0)), None);
+        assert!(plain.building.is_none());
+    }
+
+    #[test]
+    fn remove_tiles_leaves_holes() {
+        let game = capture_second_base(EliminationRule::RemoveTiles, 3);
+        assert_eq!(game.count_live_tiles(), 37 - 3);
+        assert_eq!(game.count_owned_tiles(&pubkey(&game, 1)), 0);
+        assert_eq!(game.count_owned_tiles(&pubkey(&game, 0)), 2);
+    }
+
+    #[test]
+    fn neutral_tiles_are_repopulated_with_mutants() {
+        let game = capture_second_base(EliminationRule::NeutralTiles, 3);
+        assert_eq!(game.count_live_tiles(), 37);
+        assert_eq!(game.count_owned_tiles(&pubkey(&game, 1)), 0);
+        assert_eq!(game.count_owned_tiles(&pubkey(&game, 0)), 2);
+
+        for (row, col) in [(0, 2), (0, 3), (0, 4)] {
+            let tile = game.tiles[row][col].unwrap();
+            assert!(tile.is_neutral());
+            let mutants = Tile::default_mutants(tile.level, tile.terrain);
+            assert_eq!(tile.units.map_or(0, |units| units.quantity), mutants);
+        }
+    }
+
+    #[test]
+    fn conqueror_takes_the_tiles_without_units() {
+        let game = capture_second_base(EliminationRule::TransferToConqueror, 3);
+        assert_eq!(game.count_live_tiles(), 37);
+        assert_eq!(game.count_owned_tiles(&pubkey(&game, 0)), 2 + 3);
+        for (row, col) in [(0, 2), (0, 3), (0, 4)] {
+            assert!(game.tiles[row][col].unwrap().units.is_none());
+        }
+    }
+
+    #[test]
+    fn tiles_without_a_conqueror_turn_neutral() {
+        let mut game = live_game(3, &[]);
+        game.settings.elimination_rule = EliminationRule::TransferToConqueror;
+        let leaver = pubkey(&game, 1);
+        give_tiles(&mut game, leaver, 3);
+
+        game.eliminate_player(leaver, None);
+        remove_defeated_players(&mut game).unwrap();
+
+        assert_eq!(game.count_live_tiles(), 37);
+        assert_eq!(game.count_owned_tiles(&leaver), 0);
+        assert_eq!(game.count_owned_tiles(&pubkey(&game, 0)), 1);
+        // the Base of a player who left is torn down
+        let (row, col) = game.settings.map_size.layout().base_positions[1];
+        assert!(game.tiles[row][col].unwrap().building.is_none());
+    }
+
+    #[test]
+    fn missed_turns_reset_when_the_player_ends_their_turn() {
+        let mut game = live_game(2, &[]);
+        let game_key = Pubkey::new_unique();
+
+        track_missed_turns(&mut game, game_key, true).unwrap();
+        track_missed_turns(&mut game, game_key, true).unwrap();
+        assert_eq!(game.players[0].unwrap().missed_turns, 2);
+
+        track_missed_turns(&mut game, game_key, false).unwrap();
+        let player_info = game.players[0].unwrap();
+        assert_eq!(player_info.missed_turns, 0);
+        assert!(player_info.is_alive && !player_info.is_bot);
+    }
+
+    #[test]
+    fn afk_players_are_eliminated_at_the_threshold() {
+        let mut game = live_game(2, &[]);
+        let game_key = Pubkey::new_unique();
+        let threshold = game.settings.afk_policy.threshold;
+        assert!(game.settings.afk_policy.action == AfkAction::Eliminate);
+
+        for _ in 1..threshold {
+            track_missed_turns(&mut game, game_key, true).unwrap();
+        }
+        assert!(game.players[0].unwrap().is_alive);
+
+        track_missed_turns(&mut game, game_key, true).unwrap();
+        let player_info = game.players[0].unwrap();
+        assert!(!player_info.is_alive);
+        assert_eq!(player_info.eliminated_by, None);
+
+        process_multiplayer_turn(&mut game).unwrap();
+        assert_eq!(game.count_owned_tiles(&player_info.pubkey), 0);
+        assert_eq!(game.winner, Some(pubkey(&game, 1)));
+        assert_eq!(game.outcome, Some(GameOutcome::Elimination));
+    }
+
+    #[test]
+    fn afk_players_can_be_replaced_by_a_bot() {
+        let mut game = live_game(3, &[]);
+        let game_key = Pubkey::new_unique();
+        game.settings.afk_policy = AfkPolicy {
+            threshold: 1,
+            action: AfkAction::ReplaceWithBot,
+        };
+        game.current_player_index = 1;
+
+        track_missed_turns(&mut game, game_key, true).unwrap();
+        let player_info = game.players[1].unwrap();
+        assert!(player_info.is_alive && player_info.is_bot);
+
+        // the bot plays the seat from now on and the turn skips over it
+        game.current_player_index = 0;
+        advance_turn(&mut game).unwrap();
+        assert_eq!(game.current_player_index, 2);
+        assert!(game.status == GameStatus::Live);
+    }
+
+    #[test]
+    fn afk_detection_can_be_disabled() {
+        let mut game = live_game(2, &[]);
+        game.settings.afk_policy.threshold = 0;
+
+        for _ in 0..10 {
+            track_missed_turns(&mut game, Pubkey::new_unique(), true).unwrap();
+        }
+        let player_info = game.players[0].unwrap();
+        assert_eq!(player_info.missed_turns, 10);
+        assert!(player_info.is_alive && !player_info.is_bot);
+    }
+
+    #[test]
+    fn bots_play_when_the_turn_order_reaches_them() {
+        let mut game = live_game(3, &[1]);
+        let (row, col) = game.settings.map_size.layout().base_positions[1];
+        game.tiles[row][col]
+            .as_mut()
+            .unwrap()
+            .units
+            .as_mut()
+            .unwrap()
+            .quantity = 1;
+        game.players[1].as_mut().unwrap().balance = 10;
+
+        advance_turn(&mut game).unwrap();
+
+        // the bot spent its balance on infantry and the turn moved on to the next human
+        let bot = game.players[1].unwrap();
+        assert!(bot.balance < UnitType::Infantry.cost() as u32);
+        assert_eq!(game.tiles[row][col].unwrap().owner, bot.pubkey);
+        assert_eq!(game.current_player_index, 2);
+        assert_eq!(game.round, 1);
+    }
+
+    #[test]
+    fn incomes_are_applied_once_per_round() {
+        let mut game = live_game(3, &[1]);
+        let player = game.players[0].unwrap();
+        let income: u32 = game
+            .tiles
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|tile| tile.owner == player.pubkey)
+            .map(|tile| tile.get_yield() as u32)
+            .sum();
+
+        // seat 0 to seat 2, the bot plays in between
+        advance_turn(&mut game).unwrap();
+        assert_eq!(game.players[0].unwrap().balance, player.balance);
+
+        // seat 2 back to seat 0 ends the round
+        advance_turn(&mut game).unwrap();
+        assert_eq!(game.current_player_index, 0);
+        assert_eq!(game.round, 2);
+        let player_after = game.players[0].unwrap();
+        assert_eq!(player_after.balance, player.balance + income);
+        assert_eq!(player_after.attack_points, player.attack_points + 1);
+    }
+
+    #[test]
+    fn eliminated_players_are_skipped() {
+        let mut game = live_game(3, &[]);
+        game.eliminate_player(pubkey(&game, 1), None);
+
+        advance_turn(&mut game).unwrap();
+        assert_eq!(game.current_player_index, 2);
+    }
+
+    #[test]
+    fn most_tiles_win_at_the_round_limit() {
+        let mut game = live_game(3, &[]);
+        game.settings.max_rounds = 10;
+        let leader = pubkey(&game, 2);
+        give_tiles(&mut game, leader, 2);
+
+        game.round = 10;
+        check_victory_conditions(&mut game).unwrap();
+        assert!(game.status == GameStatus::Live);
+
+        game.round = 11;
+        check_victory_conditions(&mut game).unwrap();
+        assert_eq!(game.winner, Some(leader));
+        assert_eq!(game.outcome, Some(GameOutcome::Score));
+    }
+
+    #[test]
+    fn tie_at_the_round_limit_is_a_draw() {
+        let mut game = live_game(3, &[]);
+        game.settings.max_rounds = 10;
+        game.round = 11;
+        let (first, second) = (pubkey(&game, 0), pubkey(&game, 1));
+        give_tiles(&mut game, first, 2);
+        give_tiles(&mut game, second, 2);
+
+        check_victory_conditions(&mut game).unwrap();
+        assert!(game.status == GameStatus::Completed);
+        assert_eq!(game.winner, None);
+        assert_eq!(game.outcome, Some(GameOutcome::Draw));
+    }
+
+    #[test]
+    fn games_without_a_round_limit_go_on() {
+        let mut game = live_game(2, &[]);
+        game.round = 1000;
+        check_victory_conditions(&mut game).unwrap();
+        assert!(game.status == GameStatus::Live);
+    }
+
+    #[test]
+    fn nobody_standing_is_a_draw() {
+        let mut game = live_game(2, &[]);
+        game.eliminate_player(pubkey(&game, 0), None);
+        game.eliminate_player(pubkey(&game, 1), None);
+
+        check_victory_conditions(&mut game).unwrap();
+        assert_eq!(game.winner, None);
+        assert_eq!(game.outcome, Some(GameOutcome::Draw));
+    }
+
+    #[test]
+    fn bots_left_alone_abandon_the_game() {
+        let mut game = live_game(3, &[1, 2]);
+        game.eliminate_player(pubkey(&game, 0), None);
+
+        check_victory_conditions(&mut game).unwrap();
+        assert_eq!(game.winner, None);
+        assert_eq!(game.outcome, Some(GameOutcome::Abandoned));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::errors::GameError;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+
+// Active entries are keyed by game so instructions can find them without extra state.
+// Clients list them with a memcmp on `player` and a zero `finished_at`, see the README.
+pub fn active_entry_address(profile: &Pubkey, game: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"ACTIVE_GAME", profile.as_ref(), game.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn history_entry_address(profile: &Pubkey, sequence: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"GAME_HISTORY", profile.as_ref(), &sequence.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn find_remaining_account<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    key: &Pubkey,
+) -> Result<&'info AccountInfo<'info>> {
+    accounts
+        .iter()
+        .find(|account| account.key == key)
+        .ok_or(error!(GameError::MissingGameIndexEntry))
+}
+
+pub fn init_active_entry(
+    entry: &mut GameIndexEntry,
+    player: Pubkey,
+    game: &Account<Game>,
+) -> Result<()> {
+    entry.player = player;
+    entry.game = game.key();
+    entry.game_id = game.game_id;
+    entry.joined_at = Clock::get()?.unix_timestamp;
+    entry.finished_at = 0;
+
+    Ok(())
+}
+
+// Used when a lobby is cancelled, the rent goes back to the player who paid it
+pub fn close_active_entry<'info>(
+    player: &Pubkey,
+    game_key: &Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let (profile_key, _) = Pubkey::find_program_address(&[b"PROFILE", player.as_ref()], &crate::ID);
+    let entry_key = active_entry_address(&profile_key, game_key);
+
+    let entry: Account<GameIndexEntry> =
+        Account::try_from(find_remaining_account(remaining_accounts, &entry_key)?)?;
+    let player_account = find_remaining_account(remaining_accounts, player)?;
+
+    entry.close(player_account.clone())
+}
+
+// Closes the active entries of every joined player except the creator
+pub fn close_joined_entries<'info>(
+    game: &Account<Game>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let game_key = game.key();
+
+    for player_info in game.players.iter().flatten() {
+        if !player_info.is_bot && player_info.pubkey != game.creator {
+            close_active_entry(&player_info.pubkey, &game_key, remaining_accounts)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Creates the active entry of a player seated by someone else, e.g. by matchmaking
+pub fn create_active_entry<'info>(
+    player: &Pubkey,
+    game: &Account<Game>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let (profile_key, _) = Pubkey::find_program_address(&[b"PROFILE", player.as_ref()], &crate::ID);
+    let game_key = game.key();
+
+    let (entry_key, entry_bump) = Pubkey::find_program_address(
+        &[b"ACTIVE_GAME", profile_key.as_ref(), game_key.as_ref()],
+        &crate::ID,
+    );
+    let entry_account = find_remaining_account(remaining_accounts, &entry_key)?;
+
+    let signer_seeds: &[&[u8]] = &[
+        b"ACTIVE_GAME",
+        profile_key.as_ref(),
+        game_key.as_ref(),
+        &[entry_bump],
+    ];
+    create_index_account(entry_account, signer_seeds, payer, system_program)?;
+
+    let entry = GameIndexEntry {
+        player: *player,
+        game: game_key,
+        game_id: game.game_id,
+        joined_at: Clock::get()?.unix_timestamp,
+        finished_at: 0,
+    };
+    entry.try_serialize(&mut &mut entry_account.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+// Replaces the active entry with the next entry in the player's history.
+// The payer funds the history entry and is refunded by the closed active entry.
+pub fn archive_active_entry<'info>(
+    profile: &mut Account<'info, PlayerProfile>,
+    game_key: &Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let profile_key = profile.key();
+
+    let entry_key = active_entry_address(&profile_key, game_key);
+    let active_entry: Account<GameIndexEntry> =
+        Account::try_from(find_remaining_account(remaining_accounts, &entry_key)?)?;
+
+    let sequence = profile.history_count;
+    let (history_key, history_bump) = history_entry_address(&profile_key, sequence);
+    let history_account = find_remaining_account(remaining_accounts, &history_key)?;
+
+    let sequence_bytes = sequence.to_le_bytes();
+    let signer_seeds: &[&[u8]] = &[
+        b"GAME_HISTORY",
+        profile_key.as_ref(),
+        &sequence_bytes,
+        &[history_bump],
+    ];
+    create_index_account(history_account, signer_seeds, payer, system_program)?;
+
+    let history_entry = GameIndexEntry {
+        player: active_entry.player,
+        game: active_entry.game,
+        game_id: active_entry.game_id,
+        joined_at: active_entry.joined_at,
+        finished_at: Clock::get()?.unix_timestamp,
+    };
+    history_entry.try_serialize(&mut &mut history_account.try_borrow_mut_data()?[..])?;
+
+    active_entry.close(payer.to_account_info())?;
+
+    profile.history_count += 1;
+
+    Ok(())
+}
+
+// Same as Anchor's init: anyone can send lamports to the predictable address beforehand,
+// so a funded account is topped up, allocated and assigned instead of created
+fn create_index_account<'info>(
+    account: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(GameIndexEntry::LEN);
+    let current_lamports = account.lamports();
+
+    if current_lamports == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                CreateAccount {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            GameIndexEntry::LEN as u64,
+            &crate::ID,
+        );
+    }
+
+    let required_lamports = rent.saturating_sub(current_lamports);
+    if required_lamports > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            required_lamports,
+        )?;
+    }
+
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        GameIndexEntry::LEN as u64,
+    )?;
+
+    assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )
+}
@@ -10,6 +10,19 @@ pub struct InitializeSuper<'info> {
     pub system_program: Program<'info, System>,
 }
 
+// Separate from initialize_super so deployments initialized before the lobby
+// registry and the matchmaking queue existed can still create them
+#[derive(Accounts)]
+pub struct InitializeRegistries<'info> {
+    #[account(init, payer = payer, space = LobbyRegistry::LEN, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+    #[account(init, payer = payer, space = MatchmakingQueue::LEN, seeds = [b"QUEUE"], bump)]
+    pub matchmaking_queue: Account<'info, MatchmakingQueue>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 pub fn initialize_super(ctx: Context<InitializeSuper>) -> Result<()> {
     let super_state = &mut ctx.accounts.super_state;
 
@@ -17,3 +30,10 @@ pub fn initialize_super(ctx: Context<InitializeSuper>) -> Result<()> {
 
     Ok(())
 }
+
+pub fn initialize_registries(ctx: Context<InitializeRegistries>) -> Result<()> {
+    ctx.accounts.lobby_registry.lobbies = Vec::new();
+    ctx.accounts.matchmaking_queue.entries = Vec::new();
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use crate::errors::GameError;
+use crate::events::LobbyNotListed;
+use crate::instructions::create_game::launch_game;
+use crate::instructions::game_index::close_joined_entries;
+use crate::instructions::reveal_seed::begin_reveal;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct LeaveGame<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        seeds = [b"PROFILE", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"ACTIVE_GAME", player_profile.key().as_ref(), game.key().as_ref()],
+        bump
+    )]
+    pub active_game_entry: Account<'info, GameIndexEntry>,
+}
+
+#[derive(Accounts)]
+pub struct CancelGame<'info> {
+    #[account(mut, has_one = creator, close = creator)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"PROFILE", creator.key().as_ref()],
+        bump,
+        constraint = creator_profile.player == creator.key()
+    )]
+    pub creator_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"ACTIVE_GAME", creator_profile.key().as_ref(), game.key().as_ref()],
+        bump
+    )]
+    pub active_game_entry: Account<'info, GameIndexEntry>,
+}
+
+#[derive(Accounts)]
+pub struct KickPlayer<'info> {
+    #[account(mut, has_one = creator)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+
+    pub creator: Signer<'info>,
+
+    /// CHECK: the kicked player, refunded the rent of their active game entry
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"PROFILE", player.key().as_ref()],
+        bump,
+        constraint = player_profile.player == player.key()
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"ACTIVE_GAME", player_profile.key().as_ref(), game.key().as_ref()],
+        bump
+    )]
+    pub active_game_entry: Account<'info, GameIndexEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetReady<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartGame<'info> {
+    #[account(mut, has_one = creator)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut, seeds = [b"LOBBY"], bump)]
+    pub lobby_registry: Account<'info, LobbyRegistry>,
+
+    pub creator: Signer<'info>,
+}
+
+pub fn leave_game(ctx: Context<LeaveGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+
+    if game.status != GameStatus::NotStarted {
+        return err!(GameError::GameAlreadyStarted);
+    }
+
+    if game.creator == player_pubkey {
+        return err!(GameError::CreatorCannotLeave);
+    }
+
+    free_seat(game, &player_pubkey)?;
+
+    // a full lobby is listed again once a seat opens up
+    update_lobby_listing(&mut ctx.accounts.lobby_registry, game.key(), game)?;
+
+    Ok(())
+}
+
+pub fn cancel_game<'info>(ctx: Context<'_, '_, 'info, 'info, CancelGame<'info>>) -> Result<()> {
+    let game = &ctx.accounts.game;
+
+    if game.status != GameStatus::NotStarted {
+        return err!(GameError::GameAlreadyStarted);
+    }
+
+    ctx.accounts.lobby_registry.remove(&game.key());
+
+    // Active game entries and wallets of joined players are passed as remaining accounts
+    close_joined_entries(game, ctx.remaining_accounts)?;
+
+    // Game rent is returned to the creator by the close constraint
+    Ok(())
+}
+
+pub fn kick_player(ctx: Context<KickPlayer>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+
+    if game.status != GameStatus::NotStarted {
+        return err!(GameError::GameAlreadyStarted);
+    }
+
+    if game.creator == player_pubkey {
+        return err!(GameError::CreatorCannotLeave);
+    }
+
+    free_seat(game, &player_pubkey)?;
+
+    if game.kicked.len() >= Game::MAX_KICKED {
+        return err!(GameError::KickListFull);
+    }
+    game.kicked.push(player_pubkey);
+
+    update_lobby_listing(&mut ctx.accounts.lobby_registry, game.key(), game)?;
+
+    // Active game entry rent is returned to the kicked player by the close constraint
+    Ok(())
+}
+
+pub fn set_ready(ctx: Context<SetReady>, is_ready: bool) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+
+    if game.status != GameStatus::NotStarted {
+        return err!(GameError::GameAlreadyStarted);
+    }
+
+    let player_info = game
+        .players
+        .iter_mut()
+        .flatten()
+        .find(|player_info| player_info.pubkey == player_pubkey)
+        .ok_or(GameError::InvalidPlayer)?;
+
+    player_info.is_ready = is_ready;
+
+    Ok(())
+}
+
+pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    if game.status != GameStatus::NotStarted {
+        return err!(GameError::GameAlreadyStarted);
+    }
+
+    // The creator starting the game counts as being ready
+    let all_ready = game
+        .players
+        .iter()
+        .flatten()
+        .filter(|player_info| player_info.pubkey != game.creator)
+        .all(|player_info| player_info.is_ready);
+
+    if !all_ready {
+        return err!(GameError::PlayersNotReady);
+    }
+
+    ctx.accounts.lobby_registry.remove(&game.key());
+
+    // the map is generated once every player has revealed their secret
+    if game.commit_reveal {
+        return begin_reveal(game);
+    }
+
+    // the first turn starts now, not when the lobby was created
+    game.turn_timestamp = Clock::get()?.unix_timestamp as u64;
+    launch_game(game)
+}
+
+// Public lobbies are listed while a human seat is open. Private lobbies are kept out of
+// the registry so only invited players find them. A full registry doesn't block the
+// game, it can still be joined by its address.
+pub fn update_lobby_listing(
+    lobby_registry: &mut LobbyRegistry,
+    game_key: Pubkey,
+    game: &Game,
+) -> Result<()> {
+    if game.access != GameAccess::Public {
+        return Ok(());
+    }
+
+    if game.joined_players() >= game.settings.human_seats {
+        lobby_registry.remove(&game_key);
+    } else if lobby_registry.contains(&game_key) {
+        lobby_registry.set_joined_players(&game_key, game.joined_players());
+    } else {
+        let entry = LobbyEntry::new(game_key, game, Clock::get()?.unix_timestamp);
+        if !lobby_registry.add(entry) {
+            emit!(LobbyNotListed { game: game_key });
+        }
+    }
+
+    Ok(())
+}
+
+// Frees the seat for the next player
+fn free_seat(game: &mut Game, player_pubkey: &Pubkey) -> Result<()> {
+    let player_slot = game
+        .players
+        .iter_mut()
+        .find(|player_option| {
+            player_option
+                .as_ref()
+                .is_some_and(|player_info| player_info.pubkey == *player_pubkey)
+        })
+        .ok_or(GameError::InvalidPlayer)?;
+
+    *player_slot = None;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::errors::GameError;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(name: String, row_widths: Vec<u8>, tiles: Vec<Option<TemplateTile>>)]
+pub struct CreateMapTemplate<'info> {
+    #[account(
+        init,
+        payer = author,
+        space = MapTemplate::space(&name, &row_widths, &tiles),
+        seeds = [b"MAP_TEMPLATE", author.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub map_template: Account<'info, MapTemplate>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_map_template(
+    ctx: Context<CreateMapTemplate>,
+    name: String,
+    row_widths: Vec<u8>,
+    tiles: Vec<Option<TemplateTile>>,
+    base_positions: Vec<Vec<GridPosition>>,
+) -> Result<()> {
+    let map_template = &mut ctx.accounts.map_template;
+
+    map_template.author = ctx.accounts.author.key();
+    map_template.name = name;
+    map_template.row_widths = row_widths;
+    map_template.tiles = tiles;
+    map_template.base_positions = base_positions;
+
+    if !map_template.is_valid() {
+        return err!(GameError::InvalidMapTemplate);
+    }
+
+    Ok(())
+}
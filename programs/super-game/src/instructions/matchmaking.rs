@@ -0,0 +1,224 @@
+use crate::errors::GameError;
+use crate::events::PlayersMatched;
+use crate::instructions::create_game::{setup_game, validate_settings};
+use crate::instructions::game_index::{create_active_entry, find_remaining_account};
+use crate::instructions::reveal_seed::begin_reveal;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+#[derive(Accounts)]
+pub struct EnterQueue<'info> {
+    #[account(mut, seeds = [b"QUEUE"], bump)]
+    pub matchmaking_queue: Account<'info, MatchmakingQueue>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        seeds = [b"PROFILE", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveQueue<'info> {
+    #[account(mut, seeds = [b"QUEUE"], bump)]
+    pub matchmaking_queue: Account<'info, MatchmakingQueue>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireQueueEntry<'info> {
+    #[account(mut, seeds = [b"QUEUE"], bump)]
+    pub matchmaking_queue: Account<'info, MatchmakingQueue>,
+
+    /// CHECK: receives the deposit back, must have an entry in the queue
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(map_size: MapSize)]
+pub struct MatchPlayers<'info> {
+    #[account(mut, seeds = [b"SUPER"], bump)]
+    pub super_state: Account<'info, SuperState>,
+
+    #[account(mut, seeds = [b"QUEUE"], bump)]
+    pub matchmaking_queue: Account<'info, MatchmakingQueue>,
+
+    #[account(init, payer = payer, space = Game::space(map_size, None), seeds = [b"GAME", &super_state.game_count.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn enter_queue(
+    ctx: Context<EnterQueue>,
+    map_size: MapSize,
+    player_count: u8,
+    commitment: [u8; 32],
+) -> Result<()> {
+    validate_settings(&GameSettings::matchmaking(map_size, player_count))?;
+
+    let queue = &mut ctx.accounts.matchmaking_queue;
+    let player = ctx.accounts.player.key();
+
+    if queue.contains(&player) {
+        return err!(GameError::AlreadyInQueue);
+    }
+
+    if queue.entries.len() >= MatchmakingQueue::MAX_ENTRIES {
+        return err!(GameError::MatchmakingQueueFull);
+    }
+
+    let deposit = MatchmakingQueue::deposit(map_size)?;
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.player.to_account_info(),
+                to: queue.to_account_info(),
+            },
+        ),
+        deposit,
+    )?;
+
+    queue.entries.push(QueueEntry {
+        player,
+        rating: ctx.accounts.player_profile.rating,
+        map_size,
+        player_count,
+        enqueued_at: Clock::get()?.unix_timestamp,
+        commitment,
+        deposit,
+    });
+
+    Ok(())
+}
+
+pub fn leave_queue(ctx: Context<LeaveQueue>) -> Result<()> {
+    let queue = &mut ctx.accounts.matchmaking_queue;
+    let player = ctx.accounts.player.key();
+
+    let entry = queue.remove(&player).ok_or(GameError::NotInQueue)?;
+    pay_from_queue(queue, &ctx.accounts.player, entry.deposit)
+}
+
+// Permissionless, so players who stopped waiting can't keep the queue full
+pub fn expire_queue_entry(ctx: Context<ExpireQueueEntry>) -> Result<()> {
+    let queue = &mut ctx.accounts.matchmaking_queue;
+    let player = ctx.accounts.player.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let entry = queue
+        .entries
+        .iter()
+        .find(|entry| entry.player == player)
+        .ok_or(GameError::NotInQueue)?;
+
+    if !entry.is_expired(now) {
+        return err!(GameError::QueueEntryNotExpired);
+    }
+
+    let entry = queue.remove(&player).ok_or(GameError::NotInQueue)?;
+    pay_from_queue(queue, &ctx.accounts.player, entry.deposit)
+}
+
+// Anyone can crank matchmaking for a map size. The match is chosen on-chain, so
+// the caller derives it from the queue to pass the active game entry and the
+// wallet of every matched player as remaining accounts. The caller pays the rent
+// and is paid back from the deposits with MATCH_REWARD on top. The game rent goes
+// to its creator (the longest waiting player) when it is closed, since their
+// deposit paid for it.
+pub fn match_players<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MatchPlayers<'info>>,
+    map_size: MapSize,
+) -> Result<()> {
+    let queue = &mut ctx.accounts.matchmaking_queue;
+    let now = Clock::get()?.unix_timestamp;
+    let matched = queue
+        .find_match(map_size, now)
+        .ok_or(GameError::NoMatchFound)?;
+    for entry in &matched {
+        queue.remove(&entry.player);
+    }
+
+    let entry_rent = Rent::get()?.minimum_balance(GameIndexEntry::LEN);
+    for (index, entry) in matched.iter().enumerate() {
+        if index == 0 {
+            pay_from_queue(queue, &ctx.accounts.payer, entry.deposit)?;
+        } else {
+            let player_account = find_remaining_account(ctx.remaining_accounts, &entry.player)?;
+            pay_from_queue(queue, &ctx.accounts.payer, entry_rent)?;
+            pay_from_queue(
+                queue,
+                player_account,
+                entry.deposit.saturating_sub(entry_rent),
+            )?;
+        }
+    }
+
+    let super_state = &mut ctx.accounts.super_state;
+    let game_id = super_state.game_count;
+    super_state.game_count += 1;
+
+    let settings = GameSettings::matchmaking(map_size, matched[0].player_count);
+    let game = &mut ctx.accounts.game;
+    setup_game(
+        game,
+        game_id,
+        matched[0].player,
+        settings,
+        GameAccess::Public,
+        [0; 32],
+    );
+    game.commit_reveal = true;
+
+    for (player_slot, entry) in game.players.iter_mut().zip(matched.iter()) {
+        let mut player_info = PlayerInfo::new(entry.player, false, &settings);
+        player_info.commitment = Some(entry.commitment);
+        *player_slot = Some(player_info);
+    }
+
+    for entry in &matched {
+        create_active_entry(
+            &entry.player,
+            game,
+            ctx.remaining_accounts,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )?;
+    }
+
+    // the map is generated once every matched player has revealed their secret
+    begin_reveal(game)?;
+
+    emit!(PlayersMatched {
+        game: game.key(),
+        players: matched.iter().map(|entry| entry.player).collect(),
+    });
+
+    Ok(())
+}
+
+// The queue is owned by the program, so deposits are moved out directly
+fn pay_from_queue<'info>(
+    queue: &Account<'info, MatchmakingQueue>,
+    to: &AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    let queue_info = queue.to_account_info();
+    **queue_info.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
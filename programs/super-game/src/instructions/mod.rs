@@ -1,15 +1,31 @@
 pub mod build_construction;
+pub mod close_game;
 pub mod create_game;
 pub mod end_turn;
+pub mod game_index;
 pub mod initialize_program;
+pub mod lobby;
+pub mod map_template;
+pub mod matchmaking;
 pub mod player_profile;
 pub mod recruit;
+pub mod resign_game;
+pub mod reveal_seed;
+pub mod settle_game;
 pub mod unit;
 
 pub use build_construction::*;
+pub use close_game::*;
 pub use create_game::*;
 pub use end_turn::*;
+pub use game_index::*;
 pub use initialize_program::*;
+pub use lobby::*;
+pub use map_template::*;
+pub use matchmaking::*;
 pub use player_profile::*;
 pub use recruit::*;
+pub use resign_game::*;
+pub use reveal_seed::*;
+pub use settle_game::*;
 pub use unit::*;
@@ -1,3 +1,5 @@
+use crate::errors::GameError;
+use crate::rating::INITIAL_RATING;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
@@ -16,7 +18,31 @@ pub fn create_player_profile(ctx: Context<CreatePlayerProfile>) -> Result<()> {
     profile.player = ctx.accounts.player.key();
     profile.experience = 0;
     profile.completed_games = 0;
-    profile.active_games = Vec::new();
+    profile.wins = 0;
+    profile.losses = 0;
+    profile.rating = INITIAL_RATING;
+    profile.history_count = 0;
 
     Ok(())
 }
+
+// Profiles of other participants are passed as remaining accounts,
+// returned in the same order as `players`
+pub fn load_participant_profiles<'info>(
+    players: &[Pubkey],
+    profile_accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<Account<'info, PlayerProfile>>> {
+    players
+        .iter()
+        .map(|player| {
+            let (profile_key, _) =
+                Pubkey::find_program_address(&[b"PROFILE", player.as_ref()], &crate::ID);
+            let profile_info = profile_accounts
+                .iter()
+                .find(|account| account.key() == profile_key)
+                .ok_or(GameError::MissingPlayerProfile)?;
+
+            Account::try_from(profile_info)
+        })
+        .collect()
+}
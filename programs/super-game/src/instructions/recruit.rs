@@ -1,5 +1,5 @@
 use crate::errors::GameError;
-use crate::states::{Building, BuildingType, Game, UnitType, Units};
+use crate::states::{Building, BuildingType, Game, GameStatus, UnitType, Units};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -18,6 +18,10 @@ pub fn recruit_units(
 ) -> Result<()> {
     let game = &mut ctx.accounts.game;
 
+    if game.status != GameStatus::Live {
+        return err!(GameError::GameNotLive);
+    }
+
     let row_index = row as usize;
     let col_index = col as usize;
 
@@ -40,6 +44,10 @@ pub fn recruit_units(
         }
     }
 
+    if !tile.terrain.is_passable(unit_type) {
+        return err!(GameError::ImpassableTerrain);
+    }
+
     // Check building requirements for the unit type
     match unit_type {
         UnitType::Infantry => {
@@ -75,10 +83,7 @@ pub fn recruit_units(
     let player_info = game
         .players
         .iter_mut()
-        .find(|p| {
-            p.as_ref()
-                .map_or(false, |info| info.pubkey == player_pubkey)
-        })
+        .find(|p| p.as_ref().is_some_and(|info| info.pubkey == player_pubkey))
         .and_then(|p| p.as_mut())
         .ok_or(GameError::InvalidPlayer)?;
 
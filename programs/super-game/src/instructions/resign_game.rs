@@ -0,0 +1,53 @@
+use crate::errors::GameError;
+use crate::instructions::end_turn::{
+    advance_turn, check_victory_conditions, remove_defeated_players,
+};
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ResignGame<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    pub player: Signer<'info>,
+}
+
+pub fn resign_game(ctx: Context<ResignGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+
+    if game.status != GameStatus::Live {
+        return err!(GameError::GameNotLive);
+    }
+
+    let player_index = game
+        .players
+        .iter()
+        .position(|player_option| {
+            player_option
+                .as_ref()
+                .is_some_and(|player_info| player_info.pubkey == player_pubkey)
+        })
+        .ok_or(GameError::InvalidPlayer)?;
+
+    let player_info = game.players[player_index]
+        .as_ref()
+        .ok_or(GameError::InvalidPlayer)?;
+
+    if !player_info.is_alive {
+        return err!(GameError::PlayerEliminated);
+    }
+
+    // Nobody destroyed the capital, tiles are handled as if there is no conqueror
+    game.eliminate_player(player_pubkey, None);
+
+    if game.is_multiplayer && game.current_player_index as usize == player_index {
+        advance_turn(game)?;
+        game.turn_timestamp = Clock::get()?.unix_timestamp as u64;
+    }
+
+    remove_defeated_players(game)?;
+    check_victory_conditions(game)?;
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::errors::GameError;
+use crate::instructions::create_game::launch_game;
+use crate::instructions::game_index::close_joined_entries;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+#[derive(Accounts)]
+pub struct RevealSeed<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireReveal<'info> {
+    #[account(mut, has_one = creator, close = creator)]
+    pub game: Account<'info, Game>,
+
+    /// CHECK: the game creator, refunded the game rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"PROFILE", creator.key().as_ref()],
+        bump,
+        constraint = creator_profile.player == creator.key()
+    )]
+    pub creator_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"ACTIVE_GAME", creator_profile.key().as_ref(), game.key().as_ref()],
+        bump
+    )]
+    pub active_game_entry: Account<'info, GameIndexEntry>,
+}
+
+// The player key is part of the commitment so nobody can copy another player's
+// commitment and cancel out their secret
+pub fn seed_commitment(player: &Pubkey, secret: &[u8; 32]) -> [u8; 32] {
+    hashv(&[player.as_ref(), secret.as_ref()]).to_bytes()
+}
+
+// Players get one turn time limit to reveal their secrets
+pub fn begin_reveal(game: &mut Game) -> Result<()> {
+    game.status = GameStatus::Revealing;
+    game.reveal_deadline = Clock::get()?.unix_timestamp as u64 + game.settings.turn_time_limit;
+
+    Ok(())
+}
+
+pub fn reveal_seed(ctx: Context<RevealSeed>, secret: [u8; 32]) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+
+    if game.status != GameStatus::Revealing {
+        return err!(GameError::GameNotRevealing);
+    }
+
+    let player_info = game
+        .players
+        .iter_mut()
+        .flatten()
+        .find(|player_info| player_info.pubkey == player_pubkey)
+        .ok_or(GameError::InvalidPlayer)?;
+
+    let commitment = player_info.commitment.ok_or(GameError::AlreadyRevealed)?;
+    if seed_commitment(&player_pubkey, &secret) != commitment {
+        return err!(GameError::InvalidReveal);
+    }
+    player_info.commitment = None;
+
+    // xor keeps the result independent of the reveal order
+    for (seed_byte, secret_byte) in game.seed.iter_mut().zip(secret.iter()) {
+        *seed_byte ^= secret_byte;
+    }
+
+    let all_revealed = game
+        .players
+        .iter()
+        .flatten()
+        .all(|player_info| player_info.commitment.is_none());
+
+    if all_revealed {
+        game.seed = hashv(&[game.key().as_ref(), game.seed.as_ref()]).to_bytes();
+        game.turn_timestamp = Clock::get()?.unix_timestamp as u64;
+        launch_game(game)?;
+    }
+
+    Ok(())
+}
+
+// Anyone can close a game once a player failed to reveal in time, every player
+// gets their rent back
+pub fn expire_reveal<'info>(ctx: Context<'_, '_, 'info, 'info, ExpireReveal<'info>>) -> Result<()> {
+    let game = &ctx.accounts.game;
+
+    if game.status != GameStatus::Revealing {
+        return err!(GameError::GameNotRevealing);
+    }
+
+    if (Clock::get()?.unix_timestamp as u64) < game.reveal_deadline {
+        return err!(GameError::RevealNotExpired);
+    }
+
+    // Active game entries and wallets of joined players are passed as remaining accounts
+    close_joined_entries(game, ctx.remaining_accounts)
+}
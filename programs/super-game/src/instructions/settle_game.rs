@@ -0,0 +1,97 @@
+use crate::errors::GameError;
+use crate::instructions::create_game::BOT_PUBLIC_KEYS;
+use crate::instructions::game_index::archive_active_entry;
+use crate::instructions::player_profile::load_participant_profiles;
+use crate::rating;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SettleGame<'info> {
+    #[account(mut)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless. For every human player the profile, the active game entry and
+// the next game history entry are passed as remaining accounts.
+// Runs once per game, so ratings and stats are never applied twice.
+pub fn settle_game<'info>(ctx: Context<'_, '_, 'info, 'info, SettleGame<'info>>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    if game.status != GameStatus::Completed {
+        return err!(GameError::GameNotCompleted);
+    }
+
+    if game.is_settled {
+        return err!(GameError::GameAlreadySettled);
+    }
+
+    let game_key = game.key();
+
+    // AFK seats handed over to the bot still belong to their player
+    let human_players: Vec<Pubkey> = game
+        .players
+        .iter()
+        .flatten()
+        .filter(|player_info| !BOT_PUBLIC_KEYS.contains(&player_info.pubkey))
+        .map(|player_info| player_info.pubkey)
+        .collect();
+
+    let places: Vec<u8> = human_players
+        .iter()
+        .map(|player| game.placement(player).unwrap_or(Game::MAX_PLAYERS as u8))
+        .collect();
+
+    let player_count = game.players.iter().flatten().count();
+
+    let mut profiles = load_participant_profiles(&human_players, ctx.remaining_accounts)?;
+
+    // Games against bots only are not rated
+    let rating_changes = if game.is_multiplayer {
+        let ratings: Vec<u32> = profiles.iter().map(|profile| profile.rating).collect();
+        rating::rating_changes(&ratings, &places)
+    } else {
+        vec![0; profiles.len()]
+    };
+
+    for (index, profile) in profiles.iter_mut().enumerate() {
+        let player = &human_players[index];
+        let place = places[index];
+        let is_eliminated = game
+            .players
+            .iter()
+            .flatten()
+            .any(|player_info| player_info.pubkey == *player && !player_info.is_alive);
+
+        profile.experience = profile
+            .experience
+            .saturating_add(PlayerProfile::placement_experience(place, player_count));
+        profile.completed_games = profile.completed_games.saturating_add(1);
+
+        if game.winner == Some(*player) {
+            profile.wins = profile.wins.saturating_add(1);
+        } else if game.winner.is_some() || is_eliminated {
+            profile.losses = profile.losses.saturating_add(1);
+        }
+
+        profile.rating = rating::apply_rating_change(profile.rating, rating_changes[index]);
+
+        archive_active_entry(
+            profile,
+            &game_key,
+            ctx.remaining_accounts,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )?;
+        profile.exit(&crate::ID)?;
+    }
+
+    game.is_settled = true;
+
+    Ok(())
+}
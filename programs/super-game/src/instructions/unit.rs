@@ -1,5 +1,6 @@
 use crate::errors::{GameError, UnitError};
-use crate::states::{BuildingType, Game, PlayerInfo, Tile, Units};
+use crate::hex;
+use crate::states::{Game, GameStatus, PlayerInfo, Tile, Units};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -20,6 +21,10 @@ pub fn move_unit(
     let game = &mut ctx.accounts.game;
     let player_pubkey = ctx.accounts.player.key();
 
+    if game.status != GameStatus::Live {
+        return err!(GameError::GameNotLive);
+    }
+
     validate_positions(game, from_row, from_col, to_row, to_col)?;
     let (mut from_tile, mut to_tile) = get_tiles(game, from_row, from_col, to_row, to_col)?;
 
@@ -34,7 +39,7 @@ pub fn move_unit(
 
     deduct_attack_points(game, player_pubkey, &to_tile)?;
 
-    let move_cost = calculate_move_cost(from_row, from_col, to_row, to_col)?;
+    let move_cost = calculate_move_cost(from_row, from_col, to_row, to_col, &to_tile, &from_units)?;
 
     if from_units.stamina < move_cost {
         return err!(UnitError::NotEnoughStamina);
@@ -59,6 +64,12 @@ pub fn move_unit(
             return err!(GameError::NotYourTurn);
         }
 
+        // Once the time is up anyone can end the turn, the player can't act anymore
+        let current_timestamp = Clock::get()?.unix_timestamp as u64;
+        if game.is_multiplayer && game.is_turn_expired(current_timestamp) {
+            return err!(GameError::TurnTimeExpired);
+        }
+
         handle_attack(
             game,
             from_units,
@@ -82,10 +93,8 @@ fn validate_positions(
     to_row: usize,
     to_col: usize,
 ) -> Result<()> {
-    if from_row >= game.tiles.len()
-        || from_col >= game.tiles[0].len()
-        || to_row >= game.tiles.len()
-        || to_col >= game.tiles[0].len()
+    if !hex::in_layout(&game.tiles, (from_row, from_col))
+        || !hex::in_layout(&game.tiles, (to_row, to_col))
     {
         return err!(GameError::OutOfBounds);
     }
@@ -122,7 +131,7 @@ fn get_player_info_mut(game: &mut Game, player_pubkey: Pubkey) -> Result<&mut Pl
         .position(|player_option| {
             player_option
                 .as_ref()
-                .map_or(false, |player_info| player_info.pubkey == player_pubkey)
+                .is_some_and(|player_info| player_info.pubkey == player_pubkey)
         })
         .ok_or(GameError::InvalidPlayer)?;
 
@@ -138,22 +147,19 @@ fn calculate_move_cost(
     from_col: usize,
     to_row: usize,
     to_col: usize,
+    to_tile: &Tile,
+    units: &Units,
 ) -> Result<u8> {
-    if !is_valid_move(from_row, from_col, to_row, to_col) {
+    // Units move one hex at a time
+    if !hex::are_neighbours((from_row, from_col), (to_row, to_col)) {
         return err!(UnitError::InvalidMovement);
     }
 
-    let row_diff = (from_row as isize - to_row as isize).unsigned_abs();
-    let col_diff = (from_col as isize - to_col as isize).unsigned_abs();
-    let diagonal_move = row_diff == 1 && col_diff == 1;
-    Ok(if diagonal_move { 2 } else { 1 })
-}
+    if !to_tile.terrain.is_passable(units.unit_type) {
+        return err!(GameError::ImpassableTerrain);
+    }
 
-fn is_valid_move(from_row: usize, from_col: usize, to_row: usize, to_col: usize) -> bool {
-    let row_diff = (from_row as isize - to_row as isize).unsigned_abs();
-    let col_diff = (from_col as isize - to_col as isize).unsigned_abs();
-    // Move is valid if the target tile is adjacent, including diagonals
-    row_diff <= 1 && col_diff <= 1
+    Ok(to_tile.terrain.stamina_cost(units.unit_type, units.stamina))
 }
 
 fn handle_move(
@@ -174,13 +180,19 @@ fn handle_move(
             });
             from_tile.units = None;
         } else {
-            // Swap units if possible
-            if from_units.stamina >= move_cost && to_units.stamina >= move_cost {
+            // Swap units if possible, each pays for the terrain it enters
+            if !from_tile.terrain.is_passable(to_units.unit_type) {
+                return err!(GameError::ImpassableTerrain);
+            }
+            let return_cost = from_tile
+                .terrain
+                .stamina_cost(to_units.unit_type, to_units.stamina);
+            if from_units.stamina >= move_cost && to_units.stamina >= return_cost {
                 let mut from_units_moved = from_units;
                 let mut to_units_moved = *to_units;
 
                 from_units_moved.stamina -= move_cost;
-                to_units_moved.stamina -= move_cost;
+                to_units_moved.stamina -= return_cost;
 
                 from_tile.units = Some(to_units_moved);
                 to_tile.units = Some(from_units_moved);
@@ -238,11 +250,8 @@ fn handle_attack(
             from_tile.units = None;
             to_tile.units = None;
 
-            if let Some(building) = &to_tile.building {
-                if let BuildingType::Base = building.building_type {
-                    update_player_status(game, to_tile.owner, false);
-                    to_tile.building = None;
-                }
+            if let Some(defeated_player) = to_tile.capture_capital(player_pubkey) {
+                game.eliminate_player(defeated_player, Some(player_pubkey));
             }
         }
         std::cmp::Ordering::Less => {
@@ -253,8 +262,7 @@ fn handle_attack(
                 defender_unit_strength.saturating_sub(adjusted_attacker_strength);
             if defender_unit_strength > 0 {
                 let unit_strength = to_tile.units.as_ref().unwrap().unit_type.strength() as u32;
-                let remaining_defender_units =
-                    (remaining_defender_strength + unit_strength - 1) / unit_strength;
+                let remaining_defender_units = remaining_defender_strength.div_ceil(unit_strength);
                 to_tile.units = Some(Units {
                     unit_type: to_tile.units.as_ref().unwrap().unit_type,
                     quantity: remaining_defender_units as u16,
@@ -268,8 +276,7 @@ fn handle_attack(
 
             let remaining_attacker_strength = adjusted_attacker_strength - defender_strength;
             let unit_strength = from_units.unit_type.strength() as u32;
-            let remaining_attacker_units =
-                (remaining_attacker_strength + unit_strength - 1) / unit_strength;
+            let remaining_attacker_units = remaining_attacker_strength.div_ceil(unit_strength);
             let remaining_stamina = from_units.stamina - move_cost;
 
             to_tile.units = Some(Units {
@@ -278,11 +285,8 @@ fn handle_attack(
                 stamina: remaining_stamina,
             });
 
-            if let Some(building) = &to_tile.building {
-                if let BuildingType::Base = building.building_type {
-                    update_player_status(game, to_tile.owner, false);
-                    to_tile.building = None;
-                }
+            if let Some(defeated_player) = to_tile.capture_capital(player_pubkey) {
+                game.eliminate_player(defeated_player, Some(player_pubkey));
             }
             to_tile.owner = player_pubkey;
         }
@@ -290,16 +294,45 @@ fn handle_attack(
     Ok(())
 }
 
-fn update_player_status(game: &mut Game, player_pubkey: Pubkey, is_alive: bool) {
-    if let Some(player_index) = game.players.iter().position(|player_option| {
-        if let Some(player_info) = player_option {
-            player_info.pubkey == player_pubkey
-        } else {
-            false
-        }
-    }) {
-        if let Some(player_info) = &mut game.players[player_index] {
-            player_info.is_alive = is_alive;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::{Terrain, UnitType};
+
+    fn units(unit_type: UnitType, stamina: u8) -> Units {
+        Units {
+            unit_type,
+            quantity: 1,
+            stamina,
         }
     }
+
+    #[test]
+    fn swapped_units_pay_for_the_terrain_they_enter() {
+        let mut from_tile = Tile::new(1, Terrain::Forest);
+        let mut to_tile = Tile::new(1, Terrain::Plains);
+        let plane = units(UnitType::Plane, 5);
+        from_tile.units = Some(plane);
+        to_tile.units = Some(units(UnitType::Tank, 3));
+
+        let move_cost = to_tile.terrain.stamina_cost(plane.unit_type, plane.stamina);
+        handle_move(plane, &mut from_tile, &mut to_tile, move_cost).unwrap();
+
+        // the plane enters plains for 1, the tank pays 2 to enter the forest
+        assert_eq!(to_tile.units.unwrap().unit_type, UnitType::Plane);
+        assert_eq!(to_tile.units.unwrap().stamina, 4);
+        assert_eq!(from_tile.units.unwrap().unit_type, UnitType::Tank);
+        assert_eq!(from_tile.units.unwrap().stamina, 1);
+    }
+
+    #[test]
+    fn swap_needs_stamina_on_both_sides() {
+        let mut from_tile = Tile::new(1, Terrain::Hills);
+        let mut to_tile = Tile::new(1, Terrain::Plains);
+        let tank = units(UnitType::Tank, 3);
+        from_tile.units = Some(tank);
+        to_tile.units = Some(units(UnitType::Infantry, 0));
+
+        assert!(handle_move(tank, &mut from_tile, &mut to_tile, 1).is_err());
+    }
 }
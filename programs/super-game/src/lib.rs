@@ -2,39 +2,117 @@ use anchor_lang::prelude::*;
 
 pub mod ai;
 pub mod errors;
+pub mod events;
+pub mod hex;
 pub mod instructions;
+pub mod rating;
 pub mod states;
 
 use instructions::*;
-use states::{BuildingType, MapSize, UnitType};
+use states::{
+    BuildingType, GameAccess, GameSettings, GridPosition, MapSize, TemplateTile, UnitType,
+};
 
 declare_id!("GnbCZsVXcRXVegmrQj99eSXjoQWTV1K72KYM6yocoP9S");
 
 #[program]
 pub mod super_game {
-    use states::MapSize;
-
     use super::*;
 
     pub fn initialize_program(ctx: Context<InitializeSuper>) -> Result<()> {
         instructions::initialize_program::initialize_super(ctx)
     }
 
+    pub fn initialize_registries(ctx: Context<InitializeRegistries>) -> Result<()> {
+        instructions::initialize_program::initialize_registries(ctx)
+    }
+
     pub fn create_player_profile(ctx: Context<CreatePlayerProfile>) -> Result<()> {
         instructions::player_profile::create_player_profile(ctx)
     }
 
     pub fn create_game(
         ctx: Context<CreateGame>,
-        max_players: u8,
-        is_multiplayer: bool,
+        settings: GameSettings,
+        access: GameAccess,
+        seed: Option<[u8; 32]>,
+        commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::create_game::create_game(ctx, settings, access, seed, commitment)
+    }
+
+    pub fn create_map_template(
+        ctx: Context<CreateMapTemplate>,
+        name: String,
+        row_widths: Vec<u8>,
+        tiles: Vec<Option<TemplateTile>>,
+        base_positions: Vec<Vec<GridPosition>>,
+    ) -> Result<()> {
+        instructions::map_template::create_map_template(
+            ctx,
+            name,
+            row_widths,
+            tiles,
+            base_positions,
+        )
+    }
+
+    pub fn join_game(ctx: Context<JoinGame>, commitment: Option<[u8; 32]>) -> Result<()> {
+        instructions::create_game::join_game(ctx, commitment)
+    }
+
+    pub fn leave_game(ctx: Context<LeaveGame>) -> Result<()> {
+        instructions::lobby::leave_game(ctx)
+    }
+
+    pub fn cancel_game<'info>(ctx: Context<'_, '_, 'info, 'info, CancelGame<'info>>) -> Result<()> {
+        instructions::lobby::cancel_game(ctx)
+    }
+
+    pub fn kick_player(ctx: Context<KickPlayer>) -> Result<()> {
+        instructions::lobby::kick_player(ctx)
+    }
+
+    pub fn set_ready(ctx: Context<SetReady>, is_ready: bool) -> Result<()> {
+        instructions::lobby::set_ready(ctx, is_ready)
+    }
+
+    pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
+        instructions::lobby::start_game(ctx)
+    }
+
+    pub fn enter_queue(
+        ctx: Context<EnterQueue>,
+        map_size: MapSize,
+        player_count: u8,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        instructions::matchmaking::enter_queue(ctx, map_size, player_count, commitment)
+    }
+
+    pub fn leave_queue(ctx: Context<LeaveQueue>) -> Result<()> {
+        instructions::matchmaking::leave_queue(ctx)
+    }
+
+    pub fn expire_queue_entry(ctx: Context<ExpireQueueEntry>) -> Result<()> {
+        instructions::matchmaking::expire_queue_entry(ctx)
+    }
+
+    pub fn match_players<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchPlayers<'info>>,
         map_size: MapSize,
     ) -> Result<()> {
-        instructions::create_game::create_game(ctx, max_players, is_multiplayer, map_size)
+        instructions::matchmaking::match_players(ctx, map_size)
     }
 
-    pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
-        instructions::create_game::join_game(ctx)
+    pub fn reveal_seed(ctx: Context<RevealSeed>, secret: [u8; 32]) -> Result<()> {
+        instructions::reveal_seed::reveal_seed(ctx, secret)
+    }
+
+    pub fn expire_reveal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireReveal<'info>>,
+    ) -> Result<()> {
+        instructions::reveal_seed::expire_reveal(ctx)
     }
 
     pub fn move_unit(
@@ -75,4 +153,16 @@ pub mod super_game {
     pub fn end_turn(ctx: Context<EndTurn>) -> Result<()> {
         instructions::end_turn::end_turn(ctx)
     }
+
+    pub fn resign_game(ctx: Context<ResignGame>) -> Result<()> {
+        instructions::resign_game::resign_game(ctx)
+    }
+
+    pub fn settle_game<'info>(ctx: Context<'_, '_, 'info, 'info, SettleGame<'info>>) -> Result<()> {
+        instructions::settle_game::settle_game(ctx)
+    }
+
+    pub fn close_game<'info>(ctx: Context<'_, '_, 'info, 'info, CloseGame<'info>>) -> Result<()> {
+        instructions::close_game::close_game(ctx)
+    }
 }
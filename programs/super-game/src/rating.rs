@@ -0,0 +1,82 @@
+// Elo rating, a game with more than 2 players is scored as a set of
+// pairwise matches decided by placement.
+
+pub const INITIAL_RATING: u32 = 1200;
+const K_FACTOR: f64 = 32.0;
+
+pub fn expected_score(rating: u32, opponent_rating: u32) -> f64 {
+    let difference = opponent_rating as f64 - rating as f64;
+    1.0 / (1.0 + 10f64.powf(difference / 400.0))
+}
+
+// Lower place is better, equal places are a draw
+pub fn rating_changes(ratings: &[u32], places: &[u8]) -> Vec<i32> {
+    let num_players = ratings.len();
+    if num_players < 2 {
+        return vec![0; num_players];
+    }
+
+    let k_factor = K_FACTOR / (num_players - 1) as f64;
+
+    (0..num_players)
+        .map(|player| {
+            let mut delta = 0.0;
+            for opponent in (0..num_players).filter(|&opponent| opponent != player) {
+                let actual_score = match places[player].cmp(&places[opponent]) {
+                    std::cmp::Ordering::Less => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Greater => 0.0,
+                };
+                delta += actual_score - expected_score(ratings[player], ratings[opponent]);
+            }
+            (k_factor * delta).round() as i32
+        })
+        .collect()
+}
+
+pub fn apply_rating_change(rating: u32, change: i32) -> u32 {
+    rating.saturating_add_signed(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_ratings_move_by_half_the_k_factor() {
+        assert_eq!(rating_changes(&[1200, 1200], &[1, 2]), vec![16, -16]);
+        assert_eq!(rating_changes(&[1200, 1200], &[1, 1]), vec![0, 0]);
+    }
+
+    #[test]
+    fn upsets_move_ratings_more() {
+        let favourite_wins = rating_changes(&[1600, 1200], &[1, 2]);
+        let underdog_wins = rating_changes(&[1600, 1200], &[2, 1]);
+        assert_eq!(favourite_wins, vec![3, -3]);
+        assert_eq!(underdog_wins, vec![-29, 29]);
+        // a draw still takes points from the favourite
+        assert_eq!(rating_changes(&[1600, 1200], &[1, 1]), vec![-13, 13]);
+    }
+
+    #[test]
+    fn placements_are_scored_pairwise() {
+        let changes = rating_changes(&[1200; 4], &[1, 2, 3, 4]);
+        assert_eq!(changes, vec![16, 5, -5, -16]);
+        assert_eq!(changes.iter().sum::<i32>(), 0);
+
+        // the two players sharing second place draw against each other
+        assert_eq!(rating_changes(&[1200; 3], &[1, 2, 2]), vec![16, -8, -8]);
+    }
+
+    #[test]
+    fn single_player_games_are_unrated() {
+        assert_eq!(rating_changes(&[1200], &[1]), vec![0]);
+        assert!(rating_changes(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn ratings_never_drop_below_zero() {
+        assert_eq!(apply_rating_change(1200, -16), 1184);
+        assert_eq!(apply_rating_change(10, -16), 0);
+    }
+}
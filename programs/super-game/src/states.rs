@@ -9,17 +9,209 @@ impl SuperState {
     pub const LEN: usize = 8 + 4;
 }
 
+// Multiplayer games waiting for players, one account fetch lists every open lobby
+#[account]
+pub struct LobbyRegistry {
+    pub lobbies: Vec<LobbyEntry>,
+}
+
+impl LobbyRegistry {
+    pub const MAX_LOBBIES: usize = 50;
+    pub const LEN: usize = 8 + 4 + (Self::MAX_LOBBIES * LobbyEntry::LEN);
+    pub const STALE_AFTER: i64 = 7 * 24 * 60 * 60; // 1 week, lobbies this old make room
+
+    // A full registry drops its oldest stale lobby. Returns false when nothing is
+    // stale, the game is then created without being listed.
+    pub fn add(&mut self, entry: LobbyEntry) -> bool {
+        if self.lobbies.len() >= Self::MAX_LOBBIES {
+            let stale_index = self
+                .lobbies
+                .iter()
+                .enumerate()
+                .filter(|(_, lobby)| entry.created_at - lobby.created_at > Self::STALE_AFTER)
+                .min_by_key(|(_, lobby)| lobby.created_at)
+                .map(|(index, _)| index);
+
+            match stale_index {
+                Some(index) => {
+                    self.lobbies.remove(index);
+                }
+                None => return false,
+            }
+        }
+        self.lobbies.push(entry);
+        true
+    }
+
+    pub fn contains(&self, game: &Pubkey) -> bool {
+        self.lobbies.iter().any(|lobby| lobby.game == *game)
+    }
+
+    pub fn remove(&mut self, game: &Pubkey) {
+        self.lobbies.retain(|lobby| lobby.game != *game);
+    }
+
+    pub fn set_joined_players(&mut self, game: &Pubkey, joined_players: u8) {
+        if let Some(lobby) = self.lobbies.iter_mut().find(|lobby| lobby.game == *game) {
+            lobby.joined_players = joined_players;
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LobbyEntry {
+    pub game: Pubkey,
+    pub creator: Pubkey,
+    pub map_size: MapSize,
+    pub human_seats: u8,
+    pub bot_seats: u8,
+    pub joined_players: u8,
+    pub turn_time_limit: u64,
+    pub created_at: i64,
+}
+
+impl LobbyEntry {
+    pub const LEN: usize = 32 + 32 + 1 + 1 + 1 + 1 + 8 + 8;
+
+    pub fn new(game_key: Pubkey, game: &Game, created_at: i64) -> Self {
+        Self {
+            game: game_key,
+            creator: game.creator,
+            map_size: game.settings.map_size,
+            human_seats: game.settings.human_seats,
+            bot_seats: game.settings.bot_seats,
+            joined_players: game.joined_players(),
+            turn_time_limit: game.settings.turn_time_limit,
+            created_at,
+        }
+    }
+}
+
+// Players waiting to be matched, kept in the order they entered the queue
+#[account]
+pub struct MatchmakingQueue {
+    pub entries: Vec<QueueEntry>,
+}
+
+impl MatchmakingQueue {
+    pub const MAX_ENTRIES: usize = 32;
+    pub const LEN: usize = 8 + 4 + (Self::MAX_ENTRIES * QueueEntry::LEN);
+
+    pub const ENTRY_TIMEOUT: i64 = 60 * 60; // 1 hour, then anyone can remove the entry
+    pub const MATCH_REWARD: u64 = 10_000; // lamports for whoever runs match_players
+
+    // Held while queued. The longest waiting player becomes the creator, so their
+    // deposit pays for the game, the others only pay for their active game entry.
+    pub fn deposit(map_size: MapSize) -> Result<u64> {
+        let rent = Rent::get()?;
+        Ok(rent.minimum_balance(Game::space(map_size, None))
+            + rent.minimum_balance(GameIndexEntry::LEN)
+            + Self::MATCH_REWARD)
+    }
+
+    pub fn contains(&self, player: &Pubkey) -> bool {
+        self.entries.iter().any(|entry| entry.player == *player)
+    }
+
+    // Picks the longest waiting player on the map size that can be matched,
+    // together with the compatible players closest to their rating
+    pub fn find_match(&self, map_size: MapSize, now: i64) -> Option<Vec<QueueEntry>> {
+        let waiting = || self.entries.iter().filter(|entry| !entry.is_expired(now));
+
+        for first in waiting().filter(|entry| entry.map_size == map_size) {
+            let mut candidates: Vec<&QueueEntry> = waiting()
+                .filter(|entry| entry.player != first.player && entry.is_compatible(first))
+                .collect();
+
+            let needed = first.player_count as usize - 1;
+            if candidates.len() < needed {
+                continue;
+            }
+
+            // stable sort, players waiting longer win rating ties
+            candidates.sort_by_key(|entry| entry.rating.abs_diff(first.rating));
+
+            let mut matched = vec![*first];
+            matched.extend(candidates.into_iter().take(needed).copied());
+            return Some(matched);
+        }
+
+        None
+    }
+
+    pub fn remove(&mut self, player: &Pubkey) -> Option<QueueEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.player == *player)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct QueueEntry {
+    pub player: Pubkey,
+    pub rating: u32,
+    pub map_size: MapSize,
+    pub player_count: u8,
+    pub enqueued_at: i64,
+    pub commitment: [u8; 32],
+    pub deposit: u64,
+}
+
+impl QueueEntry {
+    pub const LEN: usize = 32 + 4 + 1 + 1 + 8 + 32 + 8;
+
+    pub fn is_compatible(&self, other: &QueueEntry) -> bool {
+        self.map_size == other.map_size && self.player_count == other.player_count
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now - self.enqueued_at > MatchmakingQueue::ENTRY_TIMEOUT
+    }
+}
+
 #[account]
 pub struct PlayerProfile {
     pub player: Pubkey,
     pub experience: u32,
     pub completed_games: u32,
-    pub active_games: Vec<Pubkey>,
+    pub wins: u32,
+    pub losses: u32,
+    pub rating: u32,
+    pub history_count: u32, // next sequence number in the game history index
 }
 
 impl PlayerProfile {
-    pub const MAX_ACTIVE_GAMES: usize = 10;
-    pub const LEN: usize = 8 + 32 + 4 + 4 + 4 + (32 * Self::MAX_ACTIVE_GAMES);
+    pub const LEN: usize = 8 + 32 + 4 + 4 + 4 + 4 + 4 + 4;
+
+    pub const MAX_PLACEMENT_EXPERIENCE: u32 = 100;
+
+    // The winner gets the full amount, last place nothing, so a quick loss in a small
+    // game earns no more than a loss against a full table
+    pub fn placement_experience(place: u8, player_count: usize) -> u32 {
+        if player_count < 2 {
+            return 0;
+        }
+        let players_behind = player_count.saturating_sub(place as usize) as u32;
+        Self::MAX_PLACEMENT_EXPERIENCE * players_behind / (player_count as u32 - 1)
+    }
+}
+
+// Active games: ["ACTIVE_GAME", profile, game], closed once the game is settled or left.
+// Finished games: ["GAME_HISTORY", profile, sequence number], never closed.
+// Both indexes use the same layout, so moving an entry to history is rent neutral.
+#[account]
+pub struct GameIndexEntry {
+    pub player: Pubkey,
+    pub game: Pubkey,
+    pub game_id: u32,
+    pub joined_at: i64,
+    pub finished_at: i64, // 0 while the game is active
+}
+
+impl GameIndexEntry {
+    pub const LEN: usize = 8 + 32 + 32 + 4 + 8 + 8;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
@@ -27,12 +219,158 @@ pub enum GameStatus {
     NotStarted,
     Live,
     Completed,
+    Revealing, // waiting for every player to reveal their map seed secret
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum GameOutcome {
+    Elimination, // last player with a Base standing
+    Territory,   // controls TERRITORY_VICTORY_PERCENT of live tiles
+    Score,       // most tiles when the round limit is reached
+    Draw,        // nobody left standing or a tie on score
+    Abandoned,   // every human player is out
+}
+
+// What happens to the remaining tiles of an eliminated player
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum EliminationRule {
+    RemoveTiles,
+    NeutralTiles,
+    TransferToConqueror,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AfkAction {
+    Eliminate,
+    ReplaceWithBot,
+}
+
+// What happens to a seat after `threshold` timed out turns in a row
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AfkPolicy {
+    pub threshold: u8, // 0 disables AFK detection
+    pub action: AfkAction,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum MapSize {
     Small,
     Large,
+    Medium,
+    ExtraLarge,
+}
+
+impl MapSize {
+    pub fn layout(&self) -> &'static MapLayout {
+        &MAP_LAYOUTS[*self as usize]
+    }
+}
+
+// How tile levels are rolled, map templates bring their own levels
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MapGeneration {
+    Random,    // every tile is rolled independently
+    Symmetric, // every base gets the same neighbours, the rest of the map is mirrored
+}
+
+// Rows are centered in a square grid, base positions are (row, col) in that grid
+pub struct MapLayout {
+    pub row_widths: &'static [u8],
+    pub base_positions: [(usize, usize); Game::MAX_PLAYERS],
+}
+
+impl MapLayout {
+    pub fn grid_size(&self) -> usize {
+        self.row_widths.len()
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.row_widths.iter().map(|&width| width as usize).sum()
+    }
+}
+
+// One entry per MapSize, in declaration order. Row widths must read the same from both
+// ends so the layout keeps its hex symmetry (see hex::mirror). Every base needs all six
+// neighbours inside the layout and at least 3 tiles between bases, the first two bases
+// are mirror images of each other for 2 player games.
+const MAP_LAYOUTS: [MapLayout; 4] = [
+    MapLayout {
+        row_widths: &[3, 5, 7, 7, 7, 5, 3],
+        base_positions: [(1, 3), (5, 3), (3, 1), (3, 5)],
+    },
+    MapLayout {
+        row_widths: &[3, 5, 7, 9, 9, 9, 7, 5, 3],
+        base_positions: [(1, 4), (7, 4), (4, 1), (4, 7)],
+    },
+    MapLayout {
+        row_widths: &[4, 6, 8, 8, 8, 8, 6, 4],
+        base_positions: [(1, 4), (6, 3), (2, 1), (5, 6)],
+    },
+    MapLayout {
+        row_widths: &[5, 7, 9, 11, 11, 11, 11, 11, 9, 7, 5],
+        base_positions: [(2, 2), (8, 2), (3, 8), (7, 8)],
+    },
+];
+
+// Who may join a multiplayer lobby
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum GameAccess {
+    Public,
+    InviteOnly { invited: Vec<Pubkey> },
+    // Public key of a keypair derived from the code shared with the invited players.
+    // Joining needs a signature from it, so the code never shows up on chain.
+    JoinCode { code_signer: Pubkey },
+}
+
+impl GameAccess {
+    pub const MAX_INVITES: usize = 8;
+    pub const LEN: usize = 1 + 4 + (Self::MAX_INVITES * 32);
+}
+
+// Rules a game was created with, validated in create_game
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GameSettings {
+    pub human_seats: u8,
+    pub bot_seats: u8,
+    pub map_size: MapSize,
+    pub map_generation: MapGeneration,
+    pub elimination_rule: EliminationRule,
+    pub turn_time_limit: u64,
+    pub afk_policy: AfkPolicy,
+    pub max_rounds: u32, // 0 means no round limit
+    pub starting_balance: u32,
+    pub starting_units: u16,
+    pub starting_attack_points: u8,
+}
+
+impl GameSettings {
+    pub const MAX_STARTING_BALANCE: u32 = 100;
+    pub const MAX_STARTING_UNITS: u16 = 50;
+    pub const LEN: usize = 1 + 1 + 1 + 1 + 1 + 8 + (1 + 1) + 4 + 4 + 2 + 1;
+
+    // Settings of games created by matchmaking
+    pub fn matchmaking(map_size: MapSize, player_count: u8) -> Self {
+        Self {
+            human_seats: player_count,
+            bot_seats: 0,
+            map_size,
+            map_generation: MapGeneration::Symmetric,
+            elimination_rule: EliminationRule::RemoveTiles,
+            turn_time_limit: 60,
+            afk_policy: AfkPolicy {
+                threshold: 3,
+                action: AfkAction::Eliminate,
+            },
+            max_rounds: 0,
+            starting_balance: 2,
+            starting_units: 5,
+            starting_attack_points: 1,
+        }
+    }
+
+    pub fn total_players(&self) -> usize {
+        self.human_seats as usize + self.bot_seats as usize
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
@@ -42,6 +380,30 @@ pub struct PlayerInfo {
     pub balance: u32,
     pub attack_points: u8,
     pub is_alive: bool,
+    pub eliminated_by: Option<Pubkey>,
+    pub missed_turns: u8,
+    pub elimination_order: u8, // 0 while alive, 1 for the first player eliminated
+    pub is_ready: bool,        // lobby ready check, unused once the game is live
+    pub commitment: Option<[u8; 32]>, // map seed commitment, None once revealed
+}
+
+impl PlayerInfo {
+    pub const LEN: usize = 32 + 1 + 4 + 1 + 1 + (1 + 32) + 1 + 1 + 1 + (1 + 32);
+
+    pub fn new(pubkey: Pubkey, is_bot: bool, settings: &GameSettings) -> Self {
+        Self {
+            pubkey,
+            is_bot,
+            balance: settings.starting_balance,
+            attack_points: settings.starting_attack_points,
+            is_alive: true,
+            eliminated_by: None,
+            missed_turns: 0,
+            elimination_order: 0,
+            is_ready: false,
+            commitment: None,
+        }
+    }
 }
 
 #[account]
@@ -50,34 +412,339 @@ pub struct Game {
     pub creator: Pubkey,
     pub players: [Option<PlayerInfo>; 4],
     pub winner: Option<Pubkey>,
+    pub outcome: Option<GameOutcome>,
     pub status: GameStatus,
-    pub max_players: u8,
+    pub settings: GameSettings,
+    pub access: GameAccess,
+    pub kicked: Vec<Pubkey>, // removed from the lobby by the creator, can't join again
+    pub seed: [u8; 32],      // map seed, the same seed and settings always generate the same map
+    pub map_template: Option<Pubkey>, // tiles come from this template instead of the seed
+    pub commit_reveal: bool, // seed is combined from secrets revealed by every player
+    pub reveal_deadline: u64,
     pub is_multiplayer: bool,
-    pub map_size: MapSize,
     pub round: u32,
     pub current_player_index: u8,
     pub turn_timestamp: u64,
-    pub turn_time_limit: u64,
+    pub eliminated_count: u8,
+    pub is_settled: bool,
     pub tiles: Vec<Vec<Option<Tile>>>,
 }
 
 impl Game {
     pub const MAX_PLAYERS: usize = 4;
     pub const MAX_ATTACK_POINTS: u8 = 2;
-    pub const MAX_TILES: usize = 81;
+    pub const TERRITORY_VICTORY_PERCENT: u32 = 51;
+    pub const MIN_TURN_TIME_LIMIT: u64 = 30; // 30 seconds, blitz
+    pub const MAX_TURN_TIME_LIMIT: u64 = 7 * 24 * 60 * 60; // 1 week, correspondence
+    pub const MAX_KICKED: usize = 8;
+
+    // Everything but the tiles
+    const BASE_LEN: usize = 8
+        + 4
+        + 32
+        + (Self::MAX_PLAYERS * (1 + PlayerInfo::LEN))
+        + (1 + 32)
+        + (1 + 1)
+        + 1
+        + GameSettings::LEN
+        + GameAccess::LEN
+        + (4 + Self::MAX_KICKED * 32)
+        + 32
+        + (1 + 32)
+        + 1
+        + 8
+        + 1
+        + 4
+        + 1
+        + 8
+        + 1
+        + 1;
+
+    // Account size for a map, a template replaces the layout of the map size
+    pub fn space(map_size: MapSize, map_template: Option<&MapTemplate>) -> usize {
+        match map_template {
+            Some(template) => Self::grid_space(template.grid_size(), template.tiles.len()),
+            None => {
+                let layout = map_size.layout();
+                Self::grid_space(layout.grid_size(), layout.tile_count())
+            }
+        }
+    }
+
+    // Every grid cell stores an Option tag and live tiles a Tile
+    fn grid_space(grid_size: usize, tile_count: usize) -> usize {
+        Self::BASE_LEN + 4 + grid_size * (4 + grid_size) + tile_count * Tile::LEN
+    }
+
+    pub fn joined_players(&self) -> u8 {
+        self.players.iter().flatten().count() as u8
+    }
+
+    pub fn complete(&mut self, winner: Option<Pubkey>, outcome: GameOutcome) {
+        self.status = GameStatus::Completed;
+        self.winner = winner;
+        self.outcome = Some(outcome);
+    }
+
+    pub fn is_turn_expired(&self, current_timestamp: u64) -> bool {
+        current_timestamp.saturating_sub(self.turn_timestamp) >= self.settings.turn_time_limit
+    }
+
+    // Tiles removed from the game (holes) are not counted
+    pub fn count_live_tiles(&self) -> u32 {
+        self.tiles.iter().flatten().flatten().count() as u32
+    }
+
+    pub fn count_owned_tiles(&self, owner: &Pubkey) -> u32 {
+        self.tiles
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|tile| tile.owner == *owner)
+            .count() as u32
+    }
+
+    pub fn eliminate_player(&mut self, player_pubkey: Pubkey, conqueror: Option<Pubkey>) {
+        let elimination_order = self.eliminated_count + 1;
+
+        if let Some(player_info) = self
+            .players
+            .iter_mut()
+            .flatten()
+            .find(|player_info| player_info.pubkey == player_pubkey)
+        {
+            if player_info.is_alive {
+                player_info.is_alive = false;
+                player_info.eliminated_by = conqueror;
+                player_info.elimination_order = elimination_order;
+                self.eliminated_count = elimination_order;
+            }
+        }
+    }
+
+    // Winner first, then players still standing, then the latest eliminated.
+    // Players with the same standing share a place.
+    pub fn placement(&self, player_pubkey: &Pubkey) -> Option<u8> {
+        let standing = |player_info: &PlayerInfo| {
+            (
+                self.winner == Some(player_info.pubkey),
+                player_info.is_alive,
+                player_info.elimination_order,
+            )
+        };
+
+        let player_info = self
+            .players
+            .iter()
+            .flatten()
+            .find(|player_info| player_info.pubkey == *player_pubkey)?;
+
+        let players_ahead = self
+            .players
+            .iter()
+            .flatten()
+            .filter(|other| standing(other) > standing(player_info))
+            .count();
+
+        Some(players_ahead as u8 + 1)
+    }
+}
+
+// Map published by a designer, games created from it skip random generation
+#[account]
+pub struct MapTemplate {
+    pub author: Pubkey,
+    pub name: String,
+    pub row_widths: Vec<u8>,
+    pub tiles: Vec<Option<TemplateTile>>, // one per cell of the rows, row by row, None for holes
+    pub base_positions: Vec<Vec<GridPosition>>, // bases for 2, 3 and 4 players
+}
+
+impl MapTemplate {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_GRID_SIZE: usize = 11;
+
+    pub fn space(name: &str, row_widths: &[u8], tiles: &[Option<TemplateTile>]) -> usize {
+        let base_count: usize = (2..=Game::MAX_PLAYERS).sum();
+
+        8 + 32
+            + (4 + name.len())
+            + (4 + row_widths.len())
+            + (4 + tiles.len() * (1 + TemplateTile::LEN))
+            + (4 + (Game::MAX_PLAYERS - 1) * 4 + base_count * GridPosition::LEN)
+    }
+
+    pub fn grid_size(&self) -> usize {
+        self.row_widths.len()
+    }
+
+    // Index in `tiles` of a grid cell, None outside of the rows
+    pub fn tile_index(&self, position: &GridPosition) -> Option<usize> {
+        let row = position.row as usize;
+        let col = position.col as usize;
+        let width = *self.row_widths.get(row)? as usize;
+        let offset = (self.grid_size() - width) / 2;
+
+        if col < offset || col >= offset + width {
+            return None;
+        }
+
+        let previous_rows: usize = self.row_widths[..row].iter().map(|&w| w as usize).sum();
+        Some(previous_rows + col - offset)
+    }
 
-    pub const LEN: usize = 5000;
-    // ~ 2119 bytes
-    // 8 + 4 + 32 + (1 + (Self::MAX_PLAYERS * (32 + 1 + 4))) + 32 + 1 + 1 + 1 + 1 + (4 + (Self::MAX_TILES * Tile::LEN));
+    pub fn is_valid(&self) -> bool {
+        let grid_size = self.grid_size();
+        if self.name.len() > Self::MAX_NAME_LEN || !(1..=Self::MAX_GRID_SIZE).contains(&grid_size) {
+            return false;
+        }
 
-    pub fn get_map_layout(map_size: MapSize) -> Vec<u8> {
-        match map_size {
-            MapSize::Small => vec![3, 5, 7, 7, 7, 5, 3],
-            MapSize::Large => vec![3, 5, 7, 9, 9, 9, 7, 5, 3],
+        if self
+            .row_widths
+            .iter()
+            .any(|&width| width == 0 || width as usize > grid_size)
+        {
+            return false;
         }
+
+        let cell_count: usize = self.row_widths.iter().map(|&w| w as usize).sum();
+        if self.tiles.len() != cell_count
+            || !self.tiles.iter().flatten().all(|tile| tile.is_valid())
+        {
+            return false;
+        }
+
+        if self.base_positions.len() != Game::MAX_PLAYERS - 1 {
+            return false;
+        }
+
+        self.base_positions
+            .iter()
+            .enumerate()
+            .all(|(index, positions)| {
+                positions.len() == index + 2
+                    && positions.iter().enumerate().all(|(i, position)| {
+                        // bases go on distinct live tiles without a preset building
+                        let is_free = self
+                            .tile_index(position)
+                            .and_then(|tile_index| self.tiles[tile_index])
+                            .is_some_and(|tile| tile.building.is_none());
+                        is_free && !positions[..i].contains(position)
+                    })
+            })
+    }
+
+    // Terrain only, bases are placed when the game starts
+    pub fn build_tiles(&self) -> Vec<Vec<Option<Tile>>> {
+        let grid_size = self.grid_size();
+        let mut grid: Vec<Vec<Option<Tile>>> = vec![vec![None; grid_size]; grid_size];
+        let mut template_tiles = self.tiles.iter();
+
+        for (row_index, &tiles_in_row) in self.row_widths.iter().enumerate() {
+            let empty_spaces = (grid_size - tiles_in_row as usize) / 2;
+
+            for col_index in 0..tiles_in_row as usize {
+                if let Some(Some(template_tile)) = template_tiles.next() {
+                    grid[row_index][col_index + empty_spaces] = Some(template_tile.to_tile());
+                }
+            }
+        }
+
+        grid
+    }
+
+    pub fn bases_for(&self, player_count: usize) -> &[GridPosition] {
+        &self.base_positions[player_count - 2]
     }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct TemplateTile {
+    pub level: u8,
+    pub terrain: Terrain,
+    pub building: Option<Building>,
+    pub mutants: u16,
+}
+
+impl TemplateTile {
+    pub const LEN: usize = 1 + 1 + (1 + Building::LEN) + 2;
+
+    // Bases and captured capitals can't be preset, bases come from the base positions.
+    // Mutants and buildings need ground units to be able to stand on the tile.
+    pub fn is_valid(&self) -> bool {
+        let is_valid_level = (1..=3).contains(&self.level);
+        let is_ground = self.terrain.is_passable(UnitType::Infantry);
+        if !is_ground && (self.mutants > 0 || self.building.is_some()) {
+            return false;
+        }
+
+        let is_valid_building = match self.building {
+            Some(building) => {
+                !matches!(
+                    building.building_type,
+                    BuildingType::Base | BuildingType::CapturedCapital
+                ) && (1..=building.max_level()).contains(&building.level)
+            }
+            None => true,
+        };
+
+        is_valid_level && is_valid_building
+    }
+
+    pub fn to_tile(&self) -> Tile {
+        Tile {
+            owner: Pubkey::default(),
+            level: self.level,
+            terrain: self.terrain,
+            units: (self.mutants > 0).then_some(Units {
+                unit_type: UnitType::Mutants,
+                quantity: self.mutants,
+                stamina: 0,
+            }),
+            building: self.building,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GridPosition {
+    pub row: u8,
+    pub col: u8,
+}
+
+impl GridPosition {
+    pub const LEN: usize = 1 + 1;
+}
+
+// Final result of a game, outlives the closed Game account
+#[account]
+pub struct GameResult {
+    pub game: Pubkey,
+    pub game_id: u32,
+    pub creator: Pubkey,
+    pub players: [Option<PlayerInfo>; 4],
+    pub winner: Option<Pubkey>,
+    pub outcome: Option<GameOutcome>,
+    pub is_multiplayer: bool,
+    pub round: u32,
+    pub seed: [u8; 32],
+    pub closed_at: i64,
+}
+
+impl GameResult {
+    pub const LEN: usize = 8
+        + 32
+        + 4
+        + 32
+        + (Game::MAX_PLAYERS * (1 + PlayerInfo::LEN))
+        + (1 + 32)
+        + (1 + 1)
+        + 1
+        + 4
+        + 32
+        + 8;
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub struct Units {
     pub unit_type: UnitType,
@@ -133,6 +800,7 @@ pub enum BuildingType {
     TankFactory,
     PlaneFactory,
     Fort,
+    CapturedCapital, // former Base of an eliminated player
 }
 
 impl BuildingType {
@@ -165,6 +833,7 @@ impl Building {
                 _ => 0,
             },
             BuildingType::GasPlant => 1,
+            BuildingType::CapturedCapital => 2,
             _ => 0,
         }
     }
@@ -176,6 +845,7 @@ impl Building {
             BuildingType::TankFactory => 1,
             BuildingType::PlaneFactory => 1,
             BuildingType::Fort => 1,
+            BuildingType::CapturedCapital => 1,
         }
     }
 
@@ -199,6 +869,61 @@ impl Building {
                 _ => 0,
             },
             BuildingType::Fort => 7,
+            BuildingType::CapturedCapital => 8,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Terrain {
+    Plains,
+    Hills,
+    Forest,
+    Mountains, // planes only
+    Water,     // planes only
+    Ruins,
+}
+
+impl Terrain {
+    // Ground units and mutants can't stand on mountains or water
+    pub fn is_passable(&self, unit_type: UnitType) -> bool {
+        match self {
+            Terrain::Mountains | Terrain::Water => unit_type == UnitType::Plane,
+            _ => true,
+        }
+    }
+
+    // Extra stamina for ground units to enter the tile, planes fly over
+    pub fn move_cost(&self, unit_type: UnitType) -> u8 {
+        if unit_type == UnitType::Plane {
+            return 0;
+        }
+
+        match self {
+            Terrain::Hills | Terrain::Forest => 1,
+            _ => 0,
+        }
+    }
+
+    // Entering a tile costs 1 stamina plus the terrain cost. A unit short on stamina
+    // still gets in by spending all it has left, so infantry isn't kept off rough terrain.
+    pub fn stamina_cost(&self, unit_type: UnitType, stamina: u8) -> u8 {
+        (1 + self.move_cost(unit_type)).min(stamina.max(1))
+    }
+
+    pub fn defense_bonus(&self) -> u8 {
+        match self {
+            Terrain::Hills => 2,
+            Terrain::Forest | Terrain::Ruins => 1,
+            Terrain::Mountains => 3,
+            _ => 0,
+        }
+    }
+
+    pub fn get_yield(&self) -> u8 {
+        match self {
+            Terrain::Ruins => 1,
             _ => 0,
         }
     }
@@ -208,19 +933,21 @@ impl Building {
 pub struct Tile {
     pub owner: Pubkey,
     pub level: u8,
+    pub terrain: Terrain,
     pub units: Option<Units>,
     pub building: Option<Building>,
 }
 
 impl Tile {
-    pub const LEN: usize = 32 + 1 + 1 + Units::LEN + 1 + Building::LEN;
+    pub const LEN: usize = 32 + 1 + 1 + 1 + Units::LEN + 1 + Building::LEN;
 
-    pub fn new(level: u8) -> Self {
-        let mutants = Self::default_mutants(level);
+    pub fn new(level: u8, terrain: Terrain) -> Self {
+        let mutants = Self::default_mutants(level, terrain);
         Self {
             owner: Pubkey::default(),
             level,
-            units: Some(Units {
+            terrain,
+            units: (mutants > 0).then_some(Units {
                 unit_type: UnitType::Mutants,
                 quantity: mutants,
                 stamina: 0,
@@ -243,7 +970,7 @@ impl Tile {
             0
         };
 
-        tile_yield + building_yield
+        tile_yield + self.terrain.get_yield() + building_yield
     }
 
     pub fn is_neutral(&self) -> bool {
@@ -255,7 +982,7 @@ impl Tile {
         if self.is_neutral() {
             0
         } else {
-            let mut bonus = self.level;
+            let mut bonus = self.level + self.terrain.defense_bonus();
             if let Some(building) = &self.building {
                 if building.building_type == BuildingType::Fort {
                     bonus += building.level;
@@ -265,7 +992,30 @@ impl Tile {
         }
     }
 
-    pub fn default_mutants(level: u8) -> u16 {
+    // Hands a destroyed Base over to the attacker, returns the eliminated owner
+    pub fn capture_capital(&mut self, conqueror: Pubkey) -> Option<Pubkey> {
+        match self.building {
+            Some(Building {
+                building_type: BuildingType::Base,
+                ..
+            }) => {
+                let defeated_player = self.owner;
+                self.owner = conqueror;
+                self.building = Some(Building {
+                    building_type: BuildingType::CapturedCapital,
+                    level: 1,
+                });
+                Some(defeated_player)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn default_mutants(level: u8, terrain: Terrain) -> u16 {
+        if !terrain.is_passable(UnitType::Mutants) {
+            return 0;
+        }
+
         match level {
             1 => 1,
             2 => 3,
@@ -274,3 +1024,43 @@ impl Tile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rough_terrain_takes_the_remaining_stamina() {
+        assert_eq!(Terrain::Plains.stamina_cost(UnitType::Infantry, 1), 1);
+        assert_eq!(Terrain::Hills.stamina_cost(UnitType::Infantry, 1), 1);
+        assert_eq!(Terrain::Forest.stamina_cost(UnitType::Tank, 3), 2);
+        assert_eq!(Terrain::Hills.stamina_cost(UnitType::Plane, 5), 1);
+        // no stamina left still costs 1, so the move is refused
+        assert_eq!(Terrain::Forest.stamina_cost(UnitType::Infantry, 0), 1);
+    }
+
+    #[test]
+    fn only_planes_cross_mountains_and_water() {
+        for terrain in [Terrain::Mountains, Terrain::Water] {
+            assert!(!terrain.is_passable(UnitType::Infantry));
+            assert!(!terrain.is_passable(UnitType::Tank));
+            assert!(terrain.is_passable(UnitType::Plane));
+        }
+        assert!(Terrain::Hills.is_passable(UnitType::Infantry));
+    }
+
+    #[test]
+    fn terrain_adds_defense_to_owned_tiles_only() {
+        let mut tile = Tile::new(1, Terrain::Hills);
+        assert_eq!(tile.get_defense_bonus(), 0);
+
+        tile.owner = Pubkey::new_unique();
+        assert_eq!(tile.get_defense_bonus(), 1 + 2);
+    }
+
+    #[test]
+    fn impassable_terrain_has_no_mutants() {
+        assert!(Tile::new(3, Terrain::Water).units.is_none());
+        assert!(Tile::new(3, Terrain::Forest).units.is_some());
+    }
+}
@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { SuperGame } from "../target/types/super_game";
 
 describe("super-game", () => {
@@ -12,7 +13,73 @@ describe("super-game", () => {
   const secondPlayerKeypair = anchor.web3.Keypair.generate();
   const secondPlayer = secondPlayerKeypair.publicKey;
   const gameData = { game_id: 0 };
+  // base of the single player game and the tile its units conquer first
+  let base: [number, number];
+  let conquered: [number, number];
+  const defaultSettings = {
+    humanSeats: 1,
+    botSeats: 1,
+    mapSize: { small: {} },
+    mapGeneration: { random: {} },
+    eliminationRule: { removeTiles: {} },
+    turnTimeLimit: new anchor.BN(60),
+    afkPolicy: { threshold: 3, action: { eliminate: {} } },
+    maxRounds: 0,
+    startingBalance: 2,
+    startingUnits: 5,
+    startingAttackPoints: 1,
+  };
   let timestamp = 0;
+  const firstSecret = Array.from({ length: 32 }, () => 1);
+  const secondSecret = Array.from({ length: 32 }, () => 2);
+
+  function seedCommitment(player: anchor.web3.PublicKey, secret: number[]) {
+    return Array.from(createHash("sha256").update(player.toBuffer()).update(Buffer.from(secret)).digest());
+  }
+
+  function findActiveGameEntryPda(profile: anchor.web3.PublicKey, game: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ACTIVE_GAME"), profile.toBuffer(), game.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function findGameHistoryEntryPda(profile: anchor.web3.PublicKey, sequence: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME_HISTORY"), profile.toBuffer(), new anchor.BN(sequence).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    )[0];
+  }
+
+  // Same layout as hex.rs, even rows are drawn half a tile to the right.
+  // Neighbours come in the same clockwise order for every tile.
+  const HEX_DIRECTIONS = [
+    [1, 0],
+    [0, 1],
+    [-1, 1],
+    [-1, 0],
+    [0, -1],
+    [1, -1],
+  ];
+  function hexNeighbours(row: number, col: number): [number, number][] {
+    const q = col - (row + (row & 1)) / 2;
+    return HEX_DIRECTIONS.map(([dq, dr]) => {
+      const r = row + dr;
+      return [r, q + dq + (r + (r & 1)) / 2] as [number, number];
+    });
+  }
+
+  function findBase(game: any, player: anchor.web3.PublicKey): [number, number] {
+    for (let row = 0; row < game.tiles.length; row++) {
+      for (let col = 0; col < game.tiles[row].length; col++) {
+        const tile = game.tiles[row][col];
+        if (tile && tile.building && tile.building.buildingType.base && tile.owner.equals(player)) {
+          return [row, col];
+        }
+      }
+    }
+    throw new Error("Base not found");
+  }
 
   async function airdropSol(publicKey: anchor.web3.PublicKey, amount: number) {
     const connection = provider.connection;
@@ -53,6 +120,18 @@ describe("super-game", () => {
     }
   });
 
+  it("Initializes the lobby registry and the matchmaking queue", async () => {
+    const [lobbyRegistryPda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("LOBBY")], program.programId);
+
+    try {
+      await program.methods.initializeRegistries().rpc();
+      const lobbyRegistry = await program.account.lobbyRegistry.fetch(lobbyRegistryPda);
+      expect(lobbyRegistry.lobbies.length).to.equal(0);
+    } catch (e) {
+      expect(e.message).include("already in use");
+    }
+  });
+
   it("Creates a player profile", async () => {
     const player = provider.wallet.publicKey;
 
@@ -73,6 +152,7 @@ describe("super-game", () => {
     const playerProfile = await program.account.playerProfile.fetch(playerProfilePda);
     expect(playerProfile.player.toBase58()).to.be.equal(player.toBase58());
     expect(playerProfile.completedGames).to.be.equal(0);
+    expect(playerProfile.rating).to.be.equal(1200);
   });
 
   it("Creates a game", async () => {
@@ -96,8 +176,8 @@ describe("super-game", () => {
     );
 
     await program.methods
-      // max_players = 2, is_multiplayer = true, map_size = small
-      .createGame(2, false, { small: {} })
+      // human_seats = 1, bot_seats = 1, map_size = small
+      .createGame(defaultSettings, { public: {} }, null, null)
       .accounts({
         superState: superStatePda,
         game: gamePda,
@@ -114,11 +194,60 @@ describe("super-game", () => {
     expect(game.creator.toBase58()).to.be.equal(player.toBase58());
     expect(game.status).deep.equal({ live: {} });
     expect(game.isMultiplayer).to.be.false;
-    expect(game.mapSize).deep.equal({ small: {} });
+    expect(game.settings.mapSize).deep.equal({ small: {} });
     expect(game.tiles.length).to.equal(7);
+    expect(game.winner).to.be.null;
+    expect(game.outcome).to.be.null;
+
+    base = findBase(game, player);
+  });
+
+  it("Fails to create a game with too many players", async () => {
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .createGame({ ...defaultSettings, humanSeats: 4, botSeats: 1 }, { public: {} }, null, null)
+        .accounts({
+          superState: superStatePda,
+          game: gamePda,
+          creator: provider.wallet.publicKey,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InvalidPlayerCount");
+    }
+  });
+
+  it("Fails to move a unit to a tile that isn't a hex neighbour", async () => {
+    const player = provider.wallet.publicKey;
+    const [gamePda] = await anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(gameData.game_id).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    try {
+      // two rows down in the same column is always two hex steps away
+      await program.methods
+        .moveUnit(base[0], base[1], base[0] + 2, base[1])
+        .accounts({
+          game: gamePda,
+          player: player,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InvalidMovement");
+    }
   });
 
-  it("Fails to move a unit with 1 stamina to diagonal tile", async () => {
+  it("Fails to move a unit outside of the map layout", async () => {
     const player = provider.wallet.publicKey;
     const [gamePda] = await anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("GAME"), new anchor.BN(gameData.game_id).toArrayLike(Buffer, "le", 4)],
@@ -126,17 +255,17 @@ describe("super-game", () => {
     );
 
     try {
-      // try to move unit with 1 stamina to diagonal tile (different row index and col index)
-      // (1, 1) -> (2, 2)
+      // (0, 0) is inside the bounding square but not part of the small layout
       await program.methods
-        .moveUnit(1, 1, 2, 2)
+        .moveUnit(base[0], base[1], 0, 0)
         .accounts({
           game: gamePda,
           player: player,
         })
         .rpc();
+      throw new Error("Expected error, but transaction succeeded");
     } catch (error) {
-      expect(error.error.errorCode.code).to.equal("NotEnoughStamina");
+      expect(error.error.errorCode.code).to.equal("OutOfBounds");
     }
   });
 
@@ -148,11 +277,14 @@ describe("super-game", () => {
     );
 
     const initialGameState = await program.account.game.fetch(gamePda);
+    const units = (game: any, [row, col]: [number, number]) => game.tiles[row][col].units;
 
-    // successfully move unit from (1, 1) to (2, 1)
-    // adjacent tile in the next row
+    // neighbours of a base are open ground, attack the one with the fewest mutants
+    conquered = hexNeighbours(...base).reduce((best, neighbour) =>
+      units(initialGameState, neighbour).quantity < units(initialGameState, best).quantity ? neighbour : best
+    );
     await program.methods
-      .moveUnit(1, 1, 2, 1)
+      .moveUnit(base[0], base[1], conquered[0], conquered[1])
       .accounts({
         game: gamePda,
         player: player,
@@ -162,15 +294,15 @@ describe("super-game", () => {
     const updatedGameState = await program.account.game.fetch(gamePda);
     timestamp = updatedGameState.turnTimestamp.toNumber();
     // some units died during attack on neutral tile
-    expect(updatedGameState.tiles[2][1].units.quantity).to.equal(
-      initialGameState.tiles[1][1].units.quantity - initialGameState.tiles[2][1].units.quantity
+    expect(units(updatedGameState, conquered).quantity).to.equal(
+      units(initialGameState, base).quantity - units(initialGameState, conquered).quantity
     );
     expect(updatedGameState.players[0].attackPoints).to.equal(initialGameState.players[0].attackPoints - 1);
-    expect(updatedGameState.tiles[2][1].units.unitType).to.deep.equal({ infantry: {} });
-    expect(updatedGameState.tiles[2][1].owner.toBase58()).to.be.equal(player.toBase58());
-    expect(updatedGameState.tiles[2][1].units.stamina).to.equal(0);
+    expect(units(updatedGameState, conquered).unitType).to.deep.equal({ infantry: {} });
+    expect(updatedGameState.tiles[conquered[0]][conquered[1]].owner.toBase58()).to.be.equal(player.toBase58());
+    expect(units(updatedGameState, conquered).stamina).to.equal(0);
 
-    expect(updatedGameState.tiles[1][1].units).to.be.null;
+    expect(units(updatedGameState, base)).to.be.null;
   });
 
   it("Fails to recruit units in a tile that doesn't belong to player", async () => {
@@ -207,7 +339,7 @@ describe("super-game", () => {
     try {
       // Not enough money to recruit 100 infantry units
       await program.methods
-        .recruitUnits(unitType, 100, 1, 1)
+        .recruitUnits(unitType, 100, base[0], base[1])
         .accounts({
           game: gamePda,
           player: player,
@@ -230,9 +362,9 @@ describe("super-game", () => {
     const gameStateBefore = await program.account.game.fetch(gamePda);
     const playerInfoBefore = gameStateBefore.players[0];
 
-    // Recruit 2 infantry units at tile (1, 1)
+    // Recruit 2 infantry units on the base
     await program.methods
-      .recruitUnits(unitType, 2, 1, 1)
+      .recruitUnits(unitType, 2, base[0], base[1])
       .accounts({
         game: gamePda,
         player: player,
@@ -246,7 +378,7 @@ describe("super-game", () => {
     const totalCost = unitCost * 2;
     expect(playerInfoAfter.balance).to.equal(playerInfoBefore.balance - totalCost);
 
-    const tile = gameStateAfter.tiles[1][1];
+    const tile = gameStateAfter.tiles[base[0]][base[1]];
     expect(tile.units.quantity).to.equal(2);
     expect(tile.units.unitType).to.deep.equal({ infantry: {} });
   });
@@ -271,7 +403,7 @@ describe("super-game", () => {
     expect(updatedGame.turnTimestamp.toNumber()).to.be.greaterThan(timestamp);
     expect(updatedGame.round).to.equal(2);
     // restored stamina of unit who moved before
-    expect(updatedGame.tiles[2][1].units.stamina).to.equal(1);
+    expect(updatedGame.tiles[conquered[0]][conquered[1]].units.stamina).to.equal(1);
   });
 
   it("Fails to build Gas Plant on a tile with base", async () => {
@@ -283,7 +415,7 @@ describe("super-game", () => {
 
     try {
       await program.methods
-        .buildConstruction(1, 1, { gasPlant: {} })
+        .buildConstruction(base[0], base[1], { gasPlant: {} })
         .accounts({
           game: gamePda,
           player: player,
@@ -331,7 +463,7 @@ describe("super-game", () => {
 
     try {
       await program.methods
-        .buildConstruction(2, 1, { gasPlant: {} })
+        .buildConstruction(conquered[0], conquered[1], { gasPlant: {} })
         .accounts({
           game: gamePda,
           player: player,
@@ -369,7 +501,7 @@ describe("super-game", () => {
     expect(playerBalance).to.be.greaterThanOrEqual(12);
 
     await program.methods
-      .buildConstruction(2, 1, { gasPlant: {} })
+      .buildConstruction(conquered[0], conquered[1], { gasPlant: {} })
       .accounts({
         game: gamePda,
         player: player,
@@ -381,7 +513,7 @@ describe("super-game", () => {
 
     expect(updatedPlayerInfo.balance).to.equal(playerBalance - 12);
 
-    const tile = updatedGameState.tiles[2][1];
+    const tile = updatedGameState.tiles[conquered[0]][conquered[1]];
     expect(tile.building).to.not.be.null;
     expect(tile.building.buildingType).to.deep.equal({ gasPlant: {} });
   });
@@ -404,7 +536,12 @@ describe("super-game", () => {
     );
 
     await program.methods
-      .createGame(2, true, { small: {} })
+      .createGame(
+        { ...defaultSettings, humanSeats: 2, botSeats: 0 },
+        { public: {} },
+        null,
+        seedCommitment(player, firstSecret)
+      )
       .accounts({
         superState: superStatePda,
         game: multiplayerGamePDA,
@@ -416,6 +553,29 @@ describe("super-game", () => {
     expect(game.creator.toBase58()).to.equal(player.toBase58());
     expect(game.status).to.deep.equal({ notStarted: {} });
     expect(game.isMultiplayer).to.be.true;
+    expect(game.settings.turnTimeLimit.toNumber()).to.equal(60);
+
+    const [lobbyRegistryPda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("LOBBY")], program.programId);
+    const lobbyRegistry = await program.account.lobbyRegistry.fetch(lobbyRegistryPda);
+    const lobby = lobbyRegistry.lobbies.find((l: any) => l.game.equals(multiplayerGamePDA));
+    expect(lobby).to.not.be.undefined;
+    expect(lobby.joinedPlayers).to.equal(1);
+    expect(lobby.humanSeats).to.equal(2);
+  });
+
+  it("Fails to end a turn before the game is live", async () => {
+    try {
+      await program.methods
+        .endTurn()
+        .accounts({
+          game: multiplayerGamePDA,
+          player: provider.wallet.publicKey,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("GameNotLive");
+    }
   });
 
   it("Second player joins the game", async () => {
@@ -427,7 +587,7 @@ describe("super-game", () => {
     );
 
     await program.methods
-      .joinGame()
+      .joinGame(seedCommitment(secondPlayer, secondSecret))
       .accounts({
         game: multiplayerGamePDA,
         // @ts-ignore
@@ -438,12 +598,99 @@ describe("super-game", () => {
       .rpc();
 
     const game = await program.account.game.fetch(multiplayerGamePDA);
-    expect(game.status).to.deep.equal({ live: {} });
+    expect(game.status).to.deep.equal({ notStarted: {} });
 
     const players = game.players.filter((p: any) => p !== null);
     expect(players.length).to.equal(2);
     expect(players[0].pubkey.toBase58()).to.equal(player.toBase58());
     expect(players[1].pubkey.toBase58()).to.equal(secondPlayer.toBase58());
+
+    // every human seat is taken, the lobby is no longer listed as open
+    const [lobbyRegistryPda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("LOBBY")], program.programId);
+    const lobbyRegistry = await program.account.lobbyRegistry.fetch(lobbyRegistryPda);
+    expect(lobbyRegistry.lobbies.some((l: any) => l.game.equals(multiplayerGamePDA))).to.be.false;
+  });
+
+  it("Fails to start the game before every player is ready", async () => {
+    try {
+      await program.methods
+        .startGame()
+        .accounts({
+          game: multiplayerGamePDA,
+          creator: provider.wallet.publicKey,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("PlayersNotReady");
+    }
+  });
+
+  it("Creator starts the game once the second player is ready", async () => {
+    await program.methods
+      .setReady(true)
+      .accounts({
+        game: multiplayerGamePDA,
+        player: secondPlayer,
+      })
+      .signers([secondPlayerKeypair])
+      .rpc();
+
+    await program.methods
+      .startGame()
+      .accounts({
+        game: multiplayerGamePDA,
+        creator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const game = await program.account.game.fetch(multiplayerGamePDA);
+    expect(game.status).to.deep.equal({ revealing: {} });
+
+    const [lobbyRegistryPda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("LOBBY")], program.programId);
+    const lobbyRegistry = await program.account.lobbyRegistry.fetch(lobbyRegistryPda);
+    expect(lobbyRegistry.lobbies.some((l: any) => l.game.equals(multiplayerGamePDA))).to.be.false;
+  });
+
+  it("Fails to reveal a secret that does not match the commitment", async () => {
+    try {
+      await program.methods
+        .revealSeed(secondSecret)
+        .accounts({
+          game: multiplayerGamePDA,
+          player: provider.wallet.publicKey,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InvalidReveal");
+    }
+  });
+
+  it("Generates the map once both players revealed their secrets", async () => {
+    await program.methods
+      .revealSeed(firstSecret)
+      .accounts({
+        game: multiplayerGamePDA,
+        player: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    let game = await program.account.game.fetch(multiplayerGamePDA);
+    expect(game.status).to.deep.equal({ revealing: {} });
+
+    await program.methods
+      .revealSeed(secondSecret)
+      .accounts({
+        game: multiplayerGamePDA,
+        player: secondPlayer,
+      })
+      .signers([secondPlayerKeypair])
+      .rpc();
+
+    game = await program.account.game.fetch(multiplayerGamePDA);
+    expect(game.status).to.deep.equal({ live: {} });
+    expect(game.tiles.length).to.be.greaterThan(0);
   });
 
   it("First player ends their turn successfully", async () => {
@@ -495,5 +742,774 @@ describe("super-game", () => {
     const gameAfter = await program.account.game.fetch(multiplayerGamePDA);
     expect(gameAfter.currentPlayerIndex).to.equal(0);
   });
-  
+
+  it("Second player resigns and first player wins", async () => {
+    const player = provider.wallet.publicKey;
+
+    await program.methods
+      .resignGame()
+      .accounts({
+        game: multiplayerGamePDA,
+        player: secondPlayer,
+      })
+      .signers([secondPlayerKeypair])
+      .rpc();
+
+    const game = await program.account.game.fetch(multiplayerGamePDA);
+    expect(game.status).to.deep.equal({ completed: {} });
+    expect(game.winner.toBase58()).to.equal(player.toBase58());
+    expect(game.outcome).to.deep.equal({ elimination: {} });
+    expect(game.players[1].isAlive).to.be.false;
+  });
+
+  it("Settles the completed game and updates player profiles", async () => {
+    const player = provider.wallet.publicKey;
+    const [firstProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), player.toBuffer()],
+      program.programId
+    );
+    const [secondProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), secondPlayer.toBuffer()],
+      program.programId
+    );
+    const firstProfileBefore = await program.account.playerProfile.fetch(firstProfilePda);
+    const secondProfileBefore = await program.account.playerProfile.fetch(secondProfilePda);
+
+    // funding the next history entry up front must not block settlement
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: player,
+          toPubkey: findGameHistoryEntryPda(secondProfilePda, secondProfileBefore.historyCount),
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(0),
+        })
+      )
+    );
+
+    await program.methods
+      .settleGame()
+      .accounts({
+        game: multiplayerGamePDA,
+        payer: player,
+      })
+      .remainingAccounts(
+        [
+          [firstProfilePda, firstProfileBefore],
+          [secondProfilePda, secondProfileBefore],
+        ].flatMap(([profilePda, profile]: [anchor.web3.PublicKey, any]) => [
+          { pubkey: profilePda, isSigner: false, isWritable: true },
+          { pubkey: findActiveGameEntryPda(profilePda, multiplayerGamePDA), isSigner: false, isWritable: true },
+          { pubkey: findGameHistoryEntryPda(profilePda, profile.historyCount), isSigner: false, isWritable: true },
+        ])
+      )
+      .rpc();
+
+    const game = await program.account.game.fetch(multiplayerGamePDA);
+    expect(game.isSettled).to.be.true;
+
+    const firstProfile = await program.account.playerProfile.fetch(firstProfilePda);
+    expect(firstProfile.wins).to.equal(firstProfileBefore.wins + 1);
+    expect(firstProfile.completedGames).to.equal(firstProfileBefore.completedGames + 1);
+    expect(firstProfile.experience).to.equal(firstProfileBefore.experience + 100);
+    expect(firstProfile.rating).to.be.greaterThan(firstProfileBefore.rating);
+
+    const secondProfile = await program.account.playerProfile.fetch(secondProfilePda);
+    expect(secondProfile.losses).to.equal(secondProfileBefore.losses + 1);
+    // last place earns no experience
+    expect(secondProfile.experience).to.equal(secondProfileBefore.experience);
+    expect(secondProfile.rating).to.be.lessThan(secondProfileBefore.rating);
+    expect(secondProfile.historyCount).to.equal(secondProfileBefore.historyCount + 1);
+
+    const activeEntry = await provider.connection.getAccountInfo(
+      findActiveGameEntryPda(secondProfilePda, multiplayerGamePDA)
+    );
+    expect(activeEntry).to.be.null;
+
+    const historyEntry = await program.account.gameIndexEntry.fetch(
+      findGameHistoryEntryPda(secondProfilePda, secondProfileBefore.historyCount)
+    );
+    expect(historyEntry.game.toBase58()).to.equal(multiplayerGamePDA.toBase58());
+    expect(historyEntry.finishedAt.toNumber()).to.be.greaterThan(0);
+  });
+
+  it("Closes the completed game and records the result", async () => {
+    const player = provider.wallet.publicKey;
+    const [gameResultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("RESULT"), multiplayerGamePDA.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .closeGame()
+      .accounts({
+        game: multiplayerGamePDA,
+        // @ts-ignore
+        creator: player,
+        payer: player,
+      })
+      .rpc();
+
+    const gameAccount = await provider.connection.getAccountInfo(multiplayerGamePDA);
+    expect(gameAccount).to.be.null;
+
+    const gameResult = await program.account.gameResult.fetch(gameResultPda);
+    expect(gameResult.winner.toBase58()).to.equal(player.toBase58());
+    expect(gameResult.outcome).to.deep.equal({ elimination: {} });
+  });
+
+  it("Rejects players who are not on the invite list", async () => {
+    const player = provider.wallet.publicKey;
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const [playerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), player.toBuffer()],
+      program.programId
+    );
+    const [secondPlayerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), secondPlayer.toBuffer()],
+      program.programId
+    );
+
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [privateGamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame(
+        { ...defaultSettings, humanSeats: 2, botSeats: 0 },
+        { inviteOnly: { invited: [anchor.web3.Keypair.generate().publicKey] } },
+        null,
+        seedCommitment(player, firstSecret)
+      )
+      .accounts({
+        superState: superStatePda,
+        game: privateGamePda,
+        creator: player,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .joinGame(seedCommitment(secondPlayer, secondSecret))
+        .accounts({
+          game: privateGamePda,
+          // @ts-ignore
+          player: secondPlayer,
+          playerProfile: secondPlayerProfilePda,
+        })
+        .signers([secondPlayerKeypair])
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("NotInvited");
+    }
+
+    await program.methods
+      .cancelGame()
+      .accounts({
+        game: privateGamePda,
+        // @ts-ignore
+        creator: player,
+        creatorProfile: playerProfilePda,
+      })
+      .rpc();
+  });
+
+  it("Joins a private game by signing with the join code keypair", async () => {
+    const player = provider.wallet.publicKey;
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const [playerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), player.toBuffer()],
+      program.programId
+    );
+    const [secondPlayerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), secondPlayer.toBuffer()],
+      program.programId
+    );
+
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [privateGamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    // the code itself is shared off chain, only the derived public key is stored
+    const codeKeypair = anchor.web3.Keypair.fromSeed(createHash("sha256").update("SUPER-1234").digest());
+
+    await program.methods
+      .createGame(
+        { ...defaultSettings, humanSeats: 2, botSeats: 0 },
+        { joinCode: { codeSigner: codeKeypair.publicKey } },
+        null,
+        seedCommitment(player, firstSecret)
+      )
+      .accounts({
+        superState: superStatePda,
+        game: privateGamePda,
+        creator: player,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .joinGame(seedCommitment(secondPlayer, secondSecret))
+        .accounts({
+          game: privateGamePda,
+          // @ts-ignore
+          player: secondPlayer,
+          playerProfile: secondPlayerProfilePda,
+        })
+        .signers([secondPlayerKeypair])
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("InvalidJoinCode");
+    }
+
+    await program.methods
+      .joinGame(seedCommitment(secondPlayer, secondSecret))
+      .accounts({
+        game: privateGamePda,
+        // @ts-ignore
+        player: secondPlayer,
+        playerProfile: secondPlayerProfilePda,
+        joinCodeSigner: codeKeypair.publicKey,
+      })
+      .signers([secondPlayerKeypair, codeKeypair])
+      .rpc();
+
+    const game = await program.account.game.fetch(privateGamePda);
+    expect(game.players[1].pubkey.toBase58()).to.equal(secondPlayer.toBase58());
+
+    await program.methods
+      .cancelGame()
+      .accounts({
+        game: privateGamePda,
+        // @ts-ignore
+        creator: player,
+        creatorProfile: playerProfilePda,
+      })
+      .remainingAccounts([
+        { pubkey: findActiveGameEntryPda(secondPlayerProfilePda, privateGamePda), isSigner: false, isWritable: true },
+        { pubkey: secondPlayer, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+  });
+
+  it("Rejects a kicked player who tries to join again", async () => {
+    const player = provider.wallet.publicKey;
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const [playerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), player.toBuffer()],
+      program.programId
+    );
+    const [secondPlayerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), secondPlayer.toBuffer()],
+      program.programId
+    );
+
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [lobbyGamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame(
+        { ...defaultSettings, humanSeats: 2, botSeats: 0 },
+        { public: {} },
+        null,
+        seedCommitment(player, firstSecret)
+      )
+      .accounts({
+        superState: superStatePda,
+        game: lobbyGamePda,
+        creator: player,
+      })
+      .rpc();
+
+    const [lobbyRegistryPda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("LOBBY")], program.programId);
+    const listedLobby = async () => {
+      const lobbyRegistry = await program.account.lobbyRegistry.fetch(lobbyRegistryPda);
+      return lobbyRegistry.lobbies.find((l: any) => l.game.equals(lobbyGamePda));
+    };
+
+    const joinLobby = () =>
+      program.methods
+        .joinGame(seedCommitment(secondPlayer, secondSecret))
+        .accounts({
+          game: lobbyGamePda,
+          // @ts-ignore
+          player: secondPlayer,
+          playerProfile: secondPlayerProfilePda,
+        })
+        .signers([secondPlayerKeypair])
+        .rpc();
+
+    await joinLobby();
+    expect(await listedLobby()).to.be.undefined;
+
+    await program.methods
+      .kickPlayer()
+      .accounts({
+        game: lobbyGamePda,
+        creator: player,
+        player: secondPlayer,
+        // @ts-ignore
+        playerProfile: secondPlayerProfilePda,
+      })
+      .rpc();
+
+    const game = await program.account.game.fetch(lobbyGamePda);
+    expect(game.players.filter((p: any) => p !== null).length).to.equal(1);
+    expect(game.kicked.map((k: any) => k.toBase58())).to.deep.equal([secondPlayer.toBase58()]);
+    // the freed seat puts the lobby back in the registry
+    expect((await listedLobby()).joinedPlayers).to.equal(1);
+
+    try {
+      await joinLobby();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("PlayerKicked");
+    }
+
+    await program.methods
+      .cancelGame()
+      .accounts({
+        game: lobbyGamePda,
+        // @ts-ignore
+        creator: player,
+        creatorProfile: playerProfilePda,
+      })
+      .rpc();
+  });
+
+  it("Creator cancels a lobby and reclaims the rent", async () => {
+    const player = provider.wallet.publicKey;
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const [playerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), player.toBuffer()],
+      program.programId
+    );
+
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [lobbyGamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame(
+        { ...defaultSettings, humanSeats: 2, botSeats: 0 },
+        { public: {} },
+        null,
+        seedCommitment(player, firstSecret)
+      )
+      .accounts({
+        superState: superStatePda,
+        game: lobbyGamePda,
+        creator: player,
+      })
+      .rpc();
+
+    await program.methods
+      .cancelGame()
+      .accounts({
+        game: lobbyGamePda,
+        // @ts-ignore
+        creator: player,
+        creatorProfile: playerProfilePda,
+      })
+      .rpc();
+
+    const gameAccount = await provider.connection.getAccountInfo(lobbyGamePda);
+    expect(gameAccount).to.be.null;
+
+    const activeEntry = await provider.connection.getAccountInfo(findActiveGameEntryPda(playerProfilePda, lobbyGamePda));
+    expect(activeEntry).to.be.null;
+  });
+
+  it("Leaves the matchmaking queue and gets the deposit back", async () => {
+    const [queuePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("QUEUE")], program.programId);
+
+    await program.methods
+      .enterQueue({ small: {} }, 2, seedCommitment(secondPlayer, secondSecret))
+      .accounts({
+        player: secondPlayer,
+      })
+      .signers([secondPlayerKeypair])
+      .rpc();
+
+    let queue = await program.account.matchmakingQueue.fetch(queuePda);
+    const deposit = queue.entries[0].deposit.toNumber();
+    expect(deposit).to.be.greaterThan(0);
+    const balanceBefore = await provider.connection.getBalance(secondPlayer);
+
+    await program.methods
+      .leaveQueue()
+      .accounts({
+        player: secondPlayer,
+      })
+      .signers([secondPlayerKeypair])
+      .rpc();
+
+    queue = await program.account.matchmakingQueue.fetch(queuePda);
+    expect(queue.entries.length).to.equal(0);
+    expect(await provider.connection.getBalance(secondPlayer)).to.equal(balanceBefore + deposit);
+  });
+
+  it("Matches two queued players into a new game", async () => {
+    const player = provider.wallet.publicKey;
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const [playerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), player.toBuffer()],
+      program.programId
+    );
+    const [secondPlayerProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("PROFILE"), secondPlayer.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.enterQueue({ small: {} }, 2, seedCommitment(player, firstSecret)).rpc();
+    await program.methods
+      .enterQueue({ small: {} }, 2, seedCommitment(secondPlayer, secondSecret))
+      .accounts({
+        player: secondPlayer,
+      })
+      .signers([secondPlayerKeypair])
+      .rpc();
+
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [matchedGamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .matchPlayers({ small: {} })
+      .accounts({
+        // @ts-ignore
+        game: matchedGamePda,
+      })
+      .remainingAccounts([
+        { pubkey: findActiveGameEntryPda(playerProfilePda, matchedGamePda), isSigner: false, isWritable: true },
+        { pubkey: findActiveGameEntryPda(secondPlayerProfilePda, matchedGamePda), isSigner: false, isWritable: true },
+        // refunded the part of their deposit the creator's deposit already covered
+        { pubkey: secondPlayer, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+    const game = await program.account.game.fetch(matchedGamePda);
+    expect(game.status).to.deep.equal({ revealing: {} });
+    const players = game.players.filter((p: any) => p !== null).map((p: any) => p.pubkey.toBase58());
+    expect(players).to.deep.equal([player.toBase58(), secondPlayer.toBase58()]);
+
+    const [queuePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("QUEUE")], program.programId);
+    const queue = await program.account.matchmakingQueue.fetch(queuePda);
+    expect(queue.entries.length).to.equal(0);
+
+    const entry = await program.account.gameIndexEntry.fetch(findActiveGameEntryPda(secondPlayerProfilePda, matchedGamePda));
+    expect(entry.game.toBase58()).to.equal(matchedGamePda.toBase58());
+
+    // the active game query from the README
+    const activeEntries = await program.account.gameIndexEntry.all([
+      { dataSize: 92 },
+      { memcmp: { offset: 8, bytes: secondPlayer.toBase58() } },
+      { memcmp: { offset: 84, bytes: anchor.utils.bytes.bs58.encode(Buffer.alloc(8)) } },
+    ]);
+    const activeGames = activeEntries.map((e: any) => e.account.game.toBase58());
+    expect(activeGames).to.include(matchedGamePda.toBase58());
+    expect(activeEntries.every((e: any) => e.account.player.equals(secondPlayer))).to.be.true;
+    expect(activeEntries.every((e: any) => e.account.finishedAt.toNumber() === 0)).to.be.true;
+  });
+
+  it("Generates the same map from the same seed", async () => {
+    const player = provider.wallet.publicKey;
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const seed = Array.from({ length: 32 }, (_, i) => i);
+
+    const gamePdas = [];
+    for (let i = 0; i < 2; i++) {
+      const superState = await program.account.superState.fetch(superStatePda);
+      const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+        program.programId
+      );
+
+      await program.methods
+        .createGame(defaultSettings, { public: {} }, seed, null)
+        .accounts({
+          superState: superStatePda,
+          game: gamePda,
+          creator: player,
+        })
+        .rpc();
+      gamePdas.push(gamePda);
+    }
+
+    const [firstGame, secondGame] = await Promise.all(gamePdas.map((pda) => program.account.game.fetch(pda)));
+    expect(firstGame.seed).to.deep.equal(seed);
+    const levels = (game: any) => game.tiles.map((row: any) => row.map((tile: any) => (tile ? tile.level : null)));
+    expect(levels(firstGame)).to.deep.equal(levels(secondGame));
+  });
+
+  it("Fails to create a multiplayer game with an explicit seed", async () => {
+    const player = provider.wallet.publicKey;
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .createGame(
+          { ...defaultSettings, humanSeats: 2, botSeats: 0 },
+          { public: {} },
+          Array.from({ length: 32 }, (_, i) => i),
+          seedCommitment(player, firstSecret)
+        )
+        .accounts({
+          superState: superStatePda,
+          game: gamePda,
+          creator: player,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("ExplicitSeedNotAllowed");
+    }
+  });
+
+  it("Creates a game on an extra large map", async () => {
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame({ ...defaultSettings, mapSize: { extraLarge: {} } }, { public: {} }, null, null)
+      .accounts({
+        superState: superStatePda,
+        game: gamePda,
+        creator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.tiles.length).to.equal(11);
+    expect(game.tiles[2][2].building).to.deep.equal({ buildingType: { base: {} }, level: 1 });
+  });
+
+  it("Creates a game from a map template", async () => {
+    const player = provider.wallet.publicKey;
+    const name = "Donut";
+    const [mapTemplatePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MAP_TEMPLATE"), player.toBuffer(), Buffer.from(name)],
+      program.programId
+    );
+
+    const tile = { level: 2, terrain: { plains: {} }, building: null, mutants: 3 };
+    const tiles = [tile, tile, tile, tile, null, tile, tile, tile, tile];
+    const basePositions = [
+      [
+        { row: 0, col: 0 },
+        { row: 2, col: 2 },
+      ],
+      [
+        { row: 0, col: 0 },
+        { row: 0, col: 2 },
+        { row: 2, col: 1 },
+      ],
+      [
+        { row: 0, col: 0 },
+        { row: 0, col: 2 },
+        { row: 2, col: 0 },
+        { row: 2, col: 2 },
+      ],
+    ];
+
+    await program.methods
+      .createMapTemplate(name, Buffer.from([3, 3, 3]), tiles, basePositions)
+      .accounts({
+        author: player,
+      })
+      .rpc();
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame(defaultSettings, { public: {} }, null, null)
+      .accounts({
+        superState: superStatePda,
+        game: gamePda,
+        creator: player,
+        mapTemplate: mapTemplatePda,
+      })
+      .rpc();
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.mapTemplate.toBase58()).to.equal(mapTemplatePda.toBase58());
+    expect(game.tiles[1][1]).to.be.null;
+    expect(game.tiles[0][1].level).to.equal(2);
+    expect(game.tiles[0][0].owner.toBase58()).to.equal(player.toBase58());
+    expect(game.tiles[2][2].building).to.deep.equal({ buildingType: { base: {} }, level: 1 });
+  });
+
+  it("Applies terrain to unit moves", async () => {
+    const player = provider.wallet.publicKey;
+    const name = "Lakeside";
+    const [mapTemplatePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MAP_TEMPLATE"), player.toBuffer(), Buffer.from(name)],
+      program.programId
+    );
+
+    const plains = { level: 1, terrain: { plains: {} }, building: null, mutants: 0 };
+    const water = { level: 1, terrain: { water: {} }, building: null, mutants: 0 };
+    const hills = { level: 1, terrain: { hills: {} }, building: null, mutants: 0 };
+    // (0, 0) touches (0, 1), (1, 0) and (1, 1)
+    const tiles = [plains, water, plains, hills, null, plains, plains, plains, plains];
+    const basePositions = [
+      [
+        { row: 0, col: 0 },
+        { row: 2, col: 2 },
+      ],
+      [
+        { row: 0, col: 0 },
+        { row: 0, col: 2 },
+        { row: 2, col: 1 },
+      ],
+      [
+        { row: 0, col: 0 },
+        { row: 0, col: 2 },
+        { row: 2, col: 0 },
+        { row: 2, col: 2 },
+      ],
+    ];
+
+    await program.methods
+      .createMapTemplate(name, Buffer.from([3, 3, 3]), tiles, basePositions)
+      .accounts({
+        author: player,
+      })
+      .rpc();
+
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame(defaultSettings, { public: {} }, null, null)
+      .accounts({
+        superState: superStatePda,
+        game: gamePda,
+        creator: player,
+        mapTemplate: mapTemplatePda,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .moveUnit(0, 0, 0, 1)
+        .accounts({
+          game: gamePda,
+          player: player,
+        })
+        .rpc();
+      throw new Error("Expected error, but transaction succeeded");
+    } catch (error) {
+      expect(error.error.errorCode.code).to.equal("ImpassableTerrain");
+    }
+
+    // hills cost 2 stamina, infantry enters with the 1 it has
+    await program.methods
+      .moveUnit(0, 0, 1, 0)
+      .accounts({
+        game: gamePda,
+        player: player,
+      })
+      .rpc();
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.tiles[1][0].owner.toBase58()).to.equal(player.toBase58());
+    expect(game.tiles[1][0].units.stamina).to.equal(0);
+  });
+
+  it("Generates a symmetric map", async () => {
+    const [superStatePda] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("SUPER")], program.programId);
+    const superState = await program.account.superState.fetch(superStatePda);
+    const [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("GAME"), new anchor.BN(superState.gameCount).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+
+    await program.methods
+      .createGame(
+        { ...defaultSettings, mapSize: { large: {} }, mapGeneration: { symmetric: {} } },
+        { public: {} },
+        null,
+        null
+      )
+      .accounts({
+        superState: superStatePda,
+        game: gamePda,
+        creator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    const game = await program.account.game.fetch(gamePda);
+    const size = game.tiles.length;
+    const cell = (row: number, col: number) => {
+      const tile = game.tiles[row] && game.tiles[row][col];
+      return tile ? { level: tile.level, terrain: tile.terrain } : null;
+    };
+
+    // base positions of the large map in MAP_LAYOUTS, the first two are taken
+    const bases: [number, number][] = [
+      [1, 4],
+      [7, 4],
+      [4, 1],
+      [4, 7],
+    ];
+    expect(findBase(game, provider.wallet.publicKey)).to.deep.equal(bases[0]);
+    const rings = bases.map(([row, col]) => hexNeighbours(row, col));
+
+    // every base gets the same six neighbours, all on open ground
+    for (const ring of rings) {
+      expect(ring.map(([row, col]) => cell(row, col))).to.deep.equal(rings[0].map(([row, col]) => cell(row, col)));
+      for (const [row, col] of ring) {
+        const terrain = cell(row, col).terrain;
+        expect(terrain.plains || terrain.ruins).to.not.be.undefined;
+      }
+    }
+
+    // the rest of the map mirrors top to bottom, the hex symmetry of layouts with an odd number of rows
+    const nearBase = (row: number, col: number) =>
+      bases.some(([r, c]) => r === row && c === col) ||
+      rings.some((ring) => ring.some(([r, c]) => r === row && c === col));
+    for (let row = 0; row < size; row++) {
+      for (let col = 0; col < size; col++) {
+        const mirrored = size - 1 - row;
+        if (nearBase(row, col) || nearBase(mirrored, col)) continue;
+        expect(cell(row, col)).to.deep.equal(cell(mirrored, col));
+      }
+    }
+  });
 });